@@ -2,13 +2,39 @@ use aes_gcm::{Aes256Gcm, KeyInit, Nonce, aead::Aead};
 use core::panic;
 use directories::BaseDirs;
 use rand::RngCore;
-use std::{fs, io::Write, path::PathBuf};
+use std::{
+    fs,
+    io::{self, Write},
+    path::PathBuf,
+};
 
 use crate::tasks::Task;
 
+/// Name of the key file in the home directory.
+pub const KEY_FILE: &str = ".todotui_key";
+
 fn key_path() -> PathBuf {
     let dirs = BaseDirs::new().expect("Failed to find home directory");
-    dirs.home_dir().join(".todotui_key")
+    // A custom data dir has its own key so that separate stores don't share one. It is kept out
+    // of the data dir, which may be synced or committed to git
+    if let Some(dir) = crate::helpers::data_dir_override() {
+        let dir = std::path::absolute(dir).unwrap_or_else(|_| dir.clone());
+        let id = uuid::Uuid::new_v5(
+            &uuid::Uuid::NAMESPACE_URL,
+            dir.as_os_str().as_encoded_bytes(),
+        );
+        return dirs.home_dir().join(".todotui_keys").join(id.to_string());
+    }
+    dirs.home_dir().join(KEY_FILE)
+}
+
+// Older versions kept the key of a custom data dir inside it
+fn existing_key_path() -> PathBuf {
+    let key_file = key_path();
+    match crate::helpers::data_dir_override() {
+        Some(dir) if !key_file.exists() && dir.join(KEY_FILE).exists() => dir.join(KEY_FILE),
+        _ => key_file,
+    }
 }
 
 pub fn generate_key() {
@@ -16,6 +42,10 @@ pub fn generate_key() {
     let mut key = vec![0u8; 32]; // AES-256 key
     rand::thread_rng().fill_bytes(&mut key);
 
+    if let Some(dir) = key_file.parent() {
+        fs::create_dir_all(dir).expect("Failed to create key directory");
+    }
+
     let mut file = fs::File::create(&key_file).expect("Failed to create key file");
     file.write_all(&key).expect("Failed to write key");
 
@@ -50,7 +80,7 @@ pub fn generate_key() {
 }
 
 pub fn load_key() -> Vec<u8> {
-    let key_file = key_path();
+    let key_file = existing_key_path();
     if key_file.exists() {
         fs::read(&key_file).expect("Failed to read encryption key")
     } else {
@@ -74,14 +104,21 @@ pub fn encrypt_tasks(tasks: &[Task]) -> Vec<u8> {
     [nonce_bytes.to_vec(), ciphertext].concat()
 }
 
-pub fn decrypt_tasks(encrypted_data: &[u8]) -> Vec<Task> {
+// Returns an error instead of panicking, the file may be read while another program is writing it
+pub fn decrypt_tasks(encrypted_data: &[u8]) -> io::Result<Vec<Task>> {
     let key = load_key();
     let cipher = Aes256Gcm::new_from_slice(&key).expect("Invalid key length");
 
+    if encrypted_data.len() < 12 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "Encrypted tasks are truncated",
+        ));
+    }
     let (nonce, ciphertext) = encrypted_data.split_at(12);
     let plaintext = cipher
         .decrypt(Nonce::from_slice(nonce), ciphertext)
-        .expect("Decryption failed");
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "Decryption failed"))?;
 
-    serde_json::from_slice(&plaintext).unwrap_or_default()
+    Ok(serde_json::from_slice(&plaintext).unwrap_or_default())
 }
@@ -1,5 +1,14 @@
 use clap::Parser;
-use std::io::Result;
+use std::{
+    io::Result,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    search::Search,
+    storage::{Backend, JsonStorage},
+    tasks::Task,
+};
 
 #[derive(Parser)]
 #[clap(
@@ -14,10 +23,49 @@ pub struct Args {
     /// Generate a new encryption key.
     #[arg(short, long)]
     pub generate_key: bool,
+    /// Copy all tasks to another storage backend and switch to it.
+    #[arg(short, long, value_name = "BACKEND")]
+    pub migrate: Option<Backend>,
+    /// Directory to store tasks and the encryption key in, instead of the platform default.
+    #[arg(long, value_name = "DIR", env = "TODOTUI_DATA_DIR")]
+    pub data_dir: Option<PathBuf>,
+    /// Config file to use, instead of the platform default.
+    #[arg(long, value_name = "FILE", env = "TODOTUI_CONFIG")]
+    pub config: Option<PathBuf>,
+    /// Open the named workspace, it is created if it doesn't exist.
+    #[arg(short, long, value_name = "NAME")]
+    pub workspace: Option<String>,
+    /// Pull, merge and push the task store against the configured git remote.
+    #[arg(short, long)]
+    pub sync: bool,
+    /// Merge the tasks of another task file ( tasks.json or tasks.enc ) into the current store.
+    #[arg(long, value_name = "FILE")]
+    pub merge: Option<PathBuf>,
+    /// Common ancestor of both task files, enables deleting tasks during a merge.
+    #[arg(long, value_name = "FILE", requires = "merge")]
+    pub base: Option<PathBuf>,
+    /// Only print what a merge would change.
+    #[arg(long, requires = "merge")]
+    pub dry_run: bool,
+    /// Print the tasks matching a search, like `status:pending due:<friday`, or all tasks.
+    #[arg(
+        short,
+        long,
+        value_name = "QUERY",
+        num_args = 0..=1,
+        default_missing_value = "",
+        allow_hyphen_values = true
+    )]
+    pub list: Option<String>,
 }
 
 pub fn handle_arguments() -> Result<()> {
     let args = Args::parse();
+    // Must come first, everything below reads from these paths
+    crate::helpers::set_path_overrides(args.data_dir, args.config);
+    if let Some(name) = args.workspace {
+        crate::helpers::set_workspace_override(name);
+    }
     if args.reset {
         crate::tasks::reset()?;
         crate::auth::generate_key();
@@ -25,5 +73,94 @@ pub fn handle_arguments() -> Result<()> {
     if args.generate_key {
         crate::auth::generate_key();
     }
+    if args.sync {
+        let message = crate::sync::sync(&crate::config::Config::load())?;
+        println!("{}", message);
+        std::process::exit(0);
+    }
+    if let Some(other) = args.merge {
+        merge(&other, args.base.as_deref(), args.dry_run)?;
+        std::process::exit(0);
+    }
+    if let Some(query) = args.list {
+        list(&query)?;
+        std::process::exit(0);
+    }
+    if let Some(backend) = args.migrate {
+        migrate(backend)?;
+        std::process::exit(0);
+    }
+    Ok(())
+}
+
+fn merge(other: &Path, base: Option<&Path>, dry_run: bool) -> Result<()> {
+    let config = crate::config::Config::load();
+    let storage = crate::storage::open(&config)?;
+    let base = base.map(read_task_file).transpose()?;
+    let other_tasks = read_task_file(other)?;
+    let (merged, report) = crate::merge::merge(base.as_deref(), &storage.load()?, &other_tasks);
+
+    if dry_run {
+        println!("Dry run, nothing was saved.");
+    } else {
+        storage.save(&merged)?;
+        if crate::sync::git_config(&config).is_some() {
+            let message = format!("Merge tasks from {}", other.display());
+            crate::sync::commit(&storage.path(), &message)?;
+        }
+    }
+    print!("{}", report);
+    Ok(())
+}
+
+// Encrypted task files are recognized by their extension
+fn read_task_file(path: &Path) -> Result<Vec<Task>> {
+    let encrypted = path.extension().is_some_and(|extension| extension == "enc");
+    JsonStorage::new(PathBuf::new(), encrypted).decode(&std::fs::read(path)?)
+}
+
+fn list(query: &str) -> Result<()> {
+    let today = chrono::Local::now().date_naive();
+    let search = match Search::parse(query, today) {
+        Ok(search) => search,
+        Err(error) => {
+            eprintln!("{}", error);
+            std::process::exit(1);
+        }
+    };
+    let config = crate::config::Config::load();
+    let mut tasks = crate::storage::open(&config)?.load()?;
+    tasks.retain(|task| search.matches(task).is_some());
+    tasks.sort_by_key(|task| task.due_key());
+
+    let width = tasks.iter().map(|task| task.status.as_str().len()).max();
+    for task in &tasks {
+        let due = match (task.day(), task.time_of_day()) {
+            (Some(day), Some(time)) => day.and_time(time).format("%a %b %d %Y %H:%M").to_string(),
+            (Some(day), None) => day.format("%a %b %d %Y").to_string(),
+            (None, _) => "Someday".to_string(),
+        };
+        println!(
+            "{:<21}  {:<width$}  {}",
+            due,
+            task.status.as_str(),
+            task.title,
+            width = width.unwrap_or_default()
+        );
+    }
+    Ok(())
+}
+
+fn migrate(backend: Backend) -> Result<()> {
+    let mut config = crate::config::Config::load();
+    if config.backend() == backend {
+        println!("Tasks are already stored in this backend.");
+        return Ok(());
+    }
+    let tasks = crate::storage::open(&config)?.load()?;
+    crate::storage::open_backend(config.data_dir(), backend, config.encryption())?.save(&tasks)?;
+    config.set_backend(backend);
+    config.save();
+    println!("Migrated {} tasks.", tasks.len());
     Ok(())
 }
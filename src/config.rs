@@ -1,31 +1,290 @@
 use serde::{Deserialize, Serialize};
-use std::{fs, path::PathBuf};
+use std::{collections::BTreeMap, fs, path::PathBuf};
 
+use crate::{keybindings::Keybindings, storage::Backend, terminal::ColorSupport, theme::Theme};
+
+pub const DEFAULT_WORKSPACE: &str = "default";
+
+// The top level settings belong to the default workspace
 #[derive(Serialize, Deserialize, Clone, Default)]
 #[serde(deny_unknown_fields, default)]
 pub struct Config {
     pub encryption: bool,
+    pub backend: Backend,
+    /// The last used workspace, `None` is the default workspace
+    pub workspace: Option<String>,
+    pub git: Option<GitConfig>,
+    pub workspaces: BTreeMap<String, Workspace>,
+    #[serde(skip_serializing_if = "Keybindings::is_empty")]
+    pub keybindings: Keybindings,
+    /// `hjkl`, counts, `gg`/`G`, `dd` and a `:` command line in the task list
+    pub vim_mode: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub theme: Option<ThemeConfig>,
+    #[serde(skip_serializing_if = "TerminalConfig::is_empty")]
+    pub terminal: TerminalConfig,
+    /// Workflow statuses in display order, Pending and Completed are added if no status is
+    /// open or done
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub statuses: Vec<Status>,
+    /// Named searches, listed in the filter popup after the statuses
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub saved_searches: Vec<SavedSearch>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(deny_unknown_fields, default)]
+pub struct Workspace {
+    pub encryption: bool,
+    pub backend: Backend,
+    /// Defaults to `workspaces/<name>` inside the data dir
+    pub data_dir: Option<PathBuf>,
+    pub git: Option<GitConfig>,
+}
+
+/// Keeps the task store in a git repository, every change is committed.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields, default)]
+pub struct GitConfig {
+    /// Remote to sync with, a URL or a path to a bare repository
+    pub remote: Option<String>,
+    pub branch: String,
+}
+
+/// Overrides what is detected about the terminal.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(deny_unknown_fields, default)]
+pub struct TerminalConfig {
+    /// `truecolor`, `256` or `16`, colors of the theme are downsampled to fit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub colors: Option<ColorSupport>,
+    /// Without a Nerd Font the checkboxes are plain text
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nerd_font: Option<bool>,
+}
+
+impl TerminalConfig {
+    pub fn is_empty(&self) -> bool {
+        self.colors.is_none() && self.nerd_font.is_none()
+    }
+}
+
+/// A built-in theme, the name of a file in the themes dir or a palette of its own.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum ThemeConfig {
+    Name(String),
+    Palette(Theme),
+}
+
+/// A search kept under a name, picked like a filter.
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct SavedSearch {
+    pub name: String,
+    /// Written like in the search box, e.g. `due:today..sunday work`
+    pub query: String,
+}
+
+/// A workflow status, tasks in a done status count as completed.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Status {
+    pub name: String,
+    /// A color name like `yellow` or a hex code like `#f9e2af`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// Replaces the checkbox icon in the task list
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    #[serde(default)]
+    pub done: bool,
+}
+
+impl Status {
+    pub fn new(name: &str, done: bool) -> Self {
+        Self {
+            name: name.to_string(),
+            color: None,
+            icon: None,
+            done,
+        }
+    }
+}
+
+impl Default for GitConfig {
+    fn default() -> Self {
+        Self {
+            remote: None,
+            branch: "main".to_string(),
+        }
+    }
 }
 
 impl Config {
     fn new() -> Self {
-        Self { encryption: false }
+        Self {
+            encryption: false,
+            backend: Backend::Json,
+            workspace: None,
+            git: None,
+            workspaces: BTreeMap::new(),
+            keybindings: Keybindings::default(),
+            vim_mode: false,
+            theme: None,
+            terminal: TerminalConfig::default(),
+            statuses: Vec::new(),
+            saved_searches: Vec::new(),
+        }
+    }
+
+    /// Adds a saved search, replacing the one with the same name.
+    pub fn save_search(&mut self, search: SavedSearch) {
+        match self
+            .saved_searches
+            .iter_mut()
+            .find(|saved| saved.name == search.name)
+        {
+            Some(saved) => *saved = search,
+            None => self.saved_searches.push(search),
+        }
+    }
+
+    pub fn statuses(&self) -> Vec<Status> {
+        let mut statuses = self.statuses.clone();
+        if !statuses.iter().any(|status| !status.done) {
+            statuses.insert(0, Status::new("Pending", false));
+        }
+        if !statuses.iter().any(|status| status.done) {
+            statuses.push(Status::new("Completed", true));
+        }
+        statuses
+    }
+
+    fn active(&self) -> Option<&Workspace> {
+        self.workspace
+            .as_ref()
+            .and_then(|name| self.workspaces.get(name))
+    }
+
+    fn active_mut(&mut self) -> Option<&mut Workspace> {
+        self.workspace
+            .as_ref()
+            .and_then(|name| self.workspaces.get_mut(name))
+    }
+
+    /// Unknown theme names fall back to the default theme, which is the 16 color theme on
+    /// terminals with only 16 colors.
+    pub fn theme(&self) -> Theme {
+        match &self.theme {
+            Some(ThemeConfig::Name(name)) => crate::theme::load(name).unwrap_or_default(),
+            Some(ThemeConfig::Palette(theme)) => theme.clone(),
+            None if crate::terminal::capabilities().colors == ColorSupport::Ansi16 => {
+                crate::theme::load("16-color").unwrap_or_default()
+            }
+            None => Theme::default(),
+        }
+    }
+
+    pub fn encryption(&self) -> bool {
+        self.active()
+            .map_or(self.encryption, |workspace| workspace.encryption)
+    }
+
+    pub fn set_encryption(&mut self, encryption: bool) {
+        match self.active_mut() {
+            Some(workspace) => workspace.encryption = encryption,
+            None => self.encryption = encryption,
+        }
+    }
+
+    pub fn backend(&self) -> Backend {
+        self.active()
+            .map_or(self.backend, |workspace| workspace.backend)
+    }
+
+    pub fn set_backend(&mut self, backend: Backend) {
+        match self.active_mut() {
+            Some(workspace) => workspace.backend = backend,
+            None => self.backend = backend,
+        }
+    }
+
+    pub fn git(&self) -> Option<&GitConfig> {
+        match self.active() {
+            Some(workspace) => workspace.git.as_ref(),
+            None => self.git.as_ref(),
+        }
+    }
+
+    /// Directory of the active workspace's task store.
+    pub fn data_dir(&self) -> PathBuf {
+        let base = crate::tasks::get_data_dir();
+        match (&self.workspace, self.active()) {
+            (
+                Some(_),
+                Some(Workspace {
+                    data_dir: Some(dir),
+                    ..
+                }),
+            ) => dir.clone(),
+            (Some(name), _) => base.join("workspaces").join(name),
+            (None, _) => base,
+        }
+    }
+
+    pub fn workspace_name(&self) -> &str {
+        self.workspace.as_deref().unwrap_or(DEFAULT_WORKSPACE)
+    }
+
+    /// All workspace names, the default workspace first.
+    pub fn workspace_names(&self) -> Vec<&str> {
+        let mut names = vec![DEFAULT_WORKSPACE];
+        names.extend(self.workspaces.keys().map(String::as_str));
+        names
+    }
+
+    /// Switches to the workspace, creating it if it doesn't exist yet.
+    pub fn select_workspace(&mut self, name: &str) {
+        if name == DEFAULT_WORKSPACE {
+            self.workspace = None;
+            return;
+        }
+        self.workspaces.entry(name.to_string()).or_default();
+        self.workspace = Some(name.to_string());
     }
 
     pub fn load() -> Self {
-        let loaded: Self = match fs::read_to_string(Self::get_config_path()) {
+        let mut loaded: Self = match fs::read_to_string(Self::get_config_path()) {
             Ok(contents) => toml::from_str(&contents).unwrap(),
             Err(_) => Self::new(),
         };
+        if let Some(name) = crate::helpers::workspace_override() {
+            loaded.select_workspace(name);
+        }
+        crate::tasks::set_statuses(loaded.statuses());
+        loaded.keybindings.vim = loaded.vim_mode;
+        crate::terminal::init(&loaded.terminal);
+        crate::theme::set(loaded.theme());
         loaded
     }
 
     fn get_config_dir() -> PathBuf {
-        crate::helpers::get_project_dir().config_dir().to_path_buf()
+        match crate::helpers::config_path_override() {
+            Some(path) => path.parent().map(PathBuf::from).unwrap_or_default(),
+            None => crate::helpers::get_project_dir().config_dir().to_path_buf(),
+        }
+    }
+
+    pub fn get_themes_dir() -> PathBuf {
+        Self::get_config_dir().join("themes")
     }
 
     fn get_config_path() -> PathBuf {
-        Self::get_config_dir().join("config.toml")
+        match crate::helpers::config_path_override() {
+            Some(path) => path.clone(),
+            None => Self::get_config_dir().join("config.toml"),
+        }
     }
 
     pub fn exists(&self) -> bool {
@@ -0,0 +1,361 @@
+use chrono::{Datelike, Days, Months, NaiveDate, NaiveTime, Weekday};
+
+/// A date and time read from phrases like `tomorrow`, `next fri 5pm`, `in 3 days` or `eod`.
+#[derive(Clone, Copy, Default, PartialEq)]
+pub struct Due {
+    pub date: Option<NaiveDate>,
+    pub time: Option<NaiveTime>,
+}
+
+/// A task typed into the quick-add bar, `Pay rent tomorrow 9am #home`.
+pub struct QuickAdd {
+    /// What is left after taking out the date and time, tags included
+    pub title: String,
+    pub due: Due,
+}
+
+// Words that may lead a date or time, `on friday`, `at 5pm`
+const FILLERS: &[&str] = &["on", "at", "by", "due"];
+
+// Also plain words, `Buy sun cream` or `Write midnight story`
+const AMBIGUOUS: &[&str] = &[
+    "mon", "tue", "wed", "thu", "fri", "sat", "sun", "noon", "midnight",
+];
+
+impl Due {
+    /// Reads a whole field, `None` when any of it is not a date or time. Plain numbers like
+    /// `18 10 2026` and `14 30` are understood here, unlike in the quick-add bar.
+    pub fn parse(text: &str, today: NaiveDate) -> Option<Self> {
+        let tokens: Vec<String> = text.split_whitespace().map(str::to_lowercase).collect();
+        Self::read(&tokens, today, true)
+    }
+
+    // Lowercase tokens that all have to be dates, times or fillers
+    fn read(tokens: &[String], today: NaiveDate, numbers: bool) -> Option<Self> {
+        let mut due = Self::default();
+        let mut rest = tokens;
+        while let Some(first) = rest.first() {
+            if FILLERS.contains(&first.as_str()) && rest.len() > 1 {
+                rest = &rest[1..];
+                continue;
+            }
+            let (used, found) = phrase(rest, today, numbers)?;
+            due = due.merge(found)?;
+            rest = &rest[used..];
+        }
+        Some(due)
+    }
+
+    /// Like `Thu Oct 22 2026 17:00`, for showing what was understood.
+    pub fn describe(&self) -> String {
+        match (self.date, self.time) {
+            (Some(date), Some(time)) => {
+                format!("{} {}", date.format("%a %b %d %Y"), time.format("%H:%M"))
+            }
+            (Some(date), None) => date.format("%a %b %d %Y").to_string(),
+            (None, Some(time)) => time.format("%H:%M").to_string(),
+            (None, None) => "Someday".to_string(),
+        }
+    }
+
+    // A phrase can only set what the others left open, `tomorrow friday` is a mistake
+    fn merge(self, other: Self) -> Option<Self> {
+        if (self.date.is_some() && other.date.is_some())
+            || (self.time.is_some() && other.time.is_some())
+        {
+            return None;
+        }
+        Some(Self {
+            date: self.date.or(other.date),
+            time: self.time.or(other.time),
+        })
+    }
+}
+
+impl QuickAdd {
+    /// Takes the first date and the first time out of the text, the other words are the title.
+    /// Short weekdays like `sat` and `noon` or `midnight` only count after a filler like `on`
+    /// or at the end, where no title follows.
+    pub fn parse(text: &str, today: NaiveDate) -> Self {
+        let tokens: Vec<&str> = text.split_whitespace().collect();
+        let lowercase: Vec<String> = tokens.iter().map(|token| token.to_lowercase()).collect();
+        let mut due = Due::default();
+        let mut title: Vec<&str> = Vec::new();
+        let mut i = 0;
+        while i < tokens.len() {
+            // A filler is only dropped together with the phrase after it
+            let filler = FILLERS.contains(&lowercase[i].as_str()) as usize;
+            let found = phrase(&lowercase[i + filler..], today, false)
+                .filter(|(used, _)| {
+                    // Tags after the last phrase don't count as title
+                    let after: Vec<String> = lowercase[i + used..]
+                        .iter()
+                        .filter(|token| !token.starts_with('#'))
+                        .cloned()
+                        .collect();
+                    filler == 1
+                        || !AMBIGUOUS.contains(&lowercase[i].as_str())
+                        || Due::read(&after, today, false).is_some()
+                })
+                .and_then(|(used, found)| Some((used, due.merge(found)?)));
+            match found {
+                Some((used, merged)) => {
+                    due = merged;
+                    i += filler + used;
+                }
+                None => {
+                    title.push(tokens[i]);
+                    i += 1;
+                }
+            }
+        }
+        Self {
+            title: title.join(" "),
+            due,
+        }
+    }
+}
+
+// The longest phrase at the start of `tokens`, with how many tokens it took
+fn phrase(tokens: &[String], today: NaiveDate, numbers: bool) -> Option<(usize, Due)> {
+    let date = |date| Due {
+        date: Some(date),
+        time: None,
+    };
+    let time = |time| Due {
+        date: None,
+        time: Some(time),
+    };
+    let words: Vec<&str> = tokens.iter().take(3).map(String::as_str).collect();
+
+    if let ["in", count, unit, ..] = words.as_slice()
+        && let Some(found) = offset(today, count, unit)
+    {
+        return Some((3, date(found)));
+    }
+    if let ["next", unit, ..] = words.as_slice() {
+        let found = match *unit {
+            "week" => today.checked_add_days(Days::new(7)),
+            "month" => today.checked_add_months(Months::new(1)),
+            "year" => today.checked_add_months(Months::new(12)),
+            // The one after today, even when that is a week away
+            unit => unit
+                .parse::<Weekday>()
+                .ok()
+                .and_then(|weekday| weekday_after(today, weekday, 1)),
+        };
+        if let Some(found) = found {
+            return Some((2, date(found)));
+        }
+    }
+    if numbers
+        && let [day, month, year, ..] = words.as_slice()
+        && let Ok(found) =
+            NaiveDate::parse_from_str(&format!("{} {} {}", day, month, year), "%d %m %Y")
+    {
+        return Some((3, date(found)));
+    }
+    if numbers
+        && let [hour, minute, ..] = words.as_slice()
+        && hour.len() <= 2
+        && minute.len() == 2
+        && let Ok(found) = NaiveTime::parse_from_str(&format!("{} {}", hour, minute), "%H %M")
+    {
+        return Some((2, time(found)));
+    }
+    // A number at the end is an hour, `tomorrow 9`
+    if numbers
+        && let [hour] = words.as_slice()
+        && hour.len() <= 2
+        && let Some(found) = hour
+            .parse()
+            .ok()
+            .and_then(|hour| NaiveTime::from_hms_opt(hour, 0, 0))
+    {
+        return Some((1, time(found)));
+    }
+    if let [number, meridiem @ ("am" | "pm"), ..] = words.as_slice()
+        && let Some(found) = clock(&format!("{}{}", number, meridiem))
+    {
+        return Some((2, time(found)));
+    }
+
+    let word = *words.first()?;
+    let found = match word {
+        "today" | "eod" => date(today),
+        "tomorrow" | "tmr" => date(today.succ_opt()?),
+        "yesterday" => date(today.pred_opt()?),
+        "eow" => date(weekday_after(today, Weekday::Sun, 0)?),
+        "noon" => time(NaiveTime::from_hms_opt(12, 0, 0)?),
+        "midnight" => time(NaiveTime::MIN),
+        word => {
+            if let Ok(weekday) = word.parse::<Weekday>() {
+                // The next one, today included
+                date(weekday_after(today, weekday, 0)?)
+            } else if let Some(found) = clock(word) {
+                time(found)
+            } else {
+                date(calendar_date(word, today)?)
+            }
+        }
+    };
+    Some((1, found))
+}
+
+// `3 days`, `2 weeks`, `1 month` or `1 year` from today
+fn offset(today: NaiveDate, count: &str, unit: &str) -> Option<NaiveDate> {
+    let count: u32 = count.parse().ok()?;
+    match unit.strip_suffix('s').unwrap_or(unit) {
+        "day" => today.checked_add_days(Days::new(count.into())),
+        "week" => today.checked_add_days(Days::new(u64::from(count) * 7)),
+        "month" => today.checked_add_months(Months::new(count)),
+        "year" => today.checked_add_months(Months::new(count.checked_mul(12)?)),
+        _ => None,
+    }
+}
+
+// The first `weekday` at least `skip` days after today
+fn weekday_after(today: NaiveDate, weekday: Weekday, skip: u32) -> Option<NaiveDate> {
+    let days = (7 + weekday.num_days_from_monday() - today.weekday().num_days_from_monday() - skip)
+        % 7
+        + skip;
+    today.checked_add_days(Days::new(days.into()))
+}
+
+// `14:30`, `5pm`, `5:30pm` or `12am`
+fn clock(word: &str) -> Option<NaiveTime> {
+    let (word, offset) = match (word.strip_suffix("am"), word.strip_suffix("pm")) {
+        (Some(word), _) => (word, Some(0)),
+        (_, Some(word)) => (word, Some(12)),
+        _ => (word, None),
+    };
+    let (hour, minute) = match word.split_once(':') {
+        Some((hour, minute)) if minute.len() == 2 => {
+            (hour.parse::<u32>().ok()?, minute.parse().ok()?)
+        }
+        // Without `am` or `pm` a lone number is not a time
+        None if offset.is_some() => (word.parse::<u32>().ok()?, 0),
+        _ => return None,
+    };
+    let hour = match offset {
+        Some(offset) if (1..=12).contains(&hour) => hour % 12 + offset,
+        Some(_) => return None,
+        None => hour,
+    };
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+// `2026-10-22`, `22/10/2026` or `22/10` ( the next one )
+fn calendar_date(word: &str, today: NaiveDate) -> Option<NaiveDate> {
+    if let Some(found) = ["%Y-%m-%d", "%d/%m/%Y"]
+        .into_iter()
+        .find_map(|format| NaiveDate::parse_from_str(word, format).ok())
+    {
+        return Some(found);
+    }
+    let (day, month) = word.split_once('/')?;
+    let (day, month) = (day.parse().ok()?, month.parse().ok()?);
+    NaiveDate::from_ymd_opt(today.year(), month, day)
+        .filter(|found| *found >= today)
+        .or_else(|| NaiveDate::from_ymd_opt(today.year() + 1, month, day))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A Sunday
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, 18).unwrap()
+    }
+
+    fn day(month: u32, day: u32) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(2026, month, day)
+    }
+
+    fn hour(hour: u32, minute: u32) -> Option<NaiveTime> {
+        NaiveTime::from_hms_opt(hour, minute, 0)
+    }
+
+    fn quick(text: &str) -> (String, Option<NaiveDate>, Option<NaiveTime>) {
+        let added = QuickAdd::parse(text, today());
+        (added.title, added.due.date, added.due.time)
+    }
+
+    #[test]
+    fn parses_phrases() {
+        let parse = |text| Due::parse(text, today()).map(|due| (due.date, due.time));
+        assert_eq!(parse("tomorrow"), Some((day(10, 19), None)));
+        assert_eq!(parse("Next Fri 5pm"), Some((day(10, 23), hour(17, 0))));
+        assert_eq!(parse("in 3 days"), Some((day(10, 21), None)));
+        assert_eq!(parse("in 2 weeks"), Some((day(11, 1), None)));
+        assert_eq!(parse("sun"), Some((day(10, 18), None)));
+        assert_eq!(parse("next sunday"), Some((day(10, 25), None)));
+        assert_eq!(parse("eow"), Some((day(10, 18), None)));
+        assert_eq!(
+            parse("on 22/10 at 9:30am"),
+            Some((day(10, 22), hour(9, 30)))
+        );
+        assert_eq!(parse("20 10 2026 14 30"), Some((day(10, 20), hour(14, 30))));
+        assert_eq!(parse("tomorrow 9"), Some((day(10, 19), hour(9, 0))));
+        assert_eq!(parse("midnight"), Some((None, hour(0, 0))));
+        assert_eq!(parse(""), Some((None, None)));
+    }
+
+    #[test]
+    fn rejects_other_words_and_two_dates() {
+        assert!(Due::parse("soon", today()).is_none());
+        assert!(Due::parse("tomorrow friday", today()).is_none());
+        assert!(Due::parse("5pm noon", today()).is_none());
+        assert!(Due::parse("13pm", today()).is_none());
+    }
+
+    #[test]
+    fn takes_the_due_out_of_quick_adds() {
+        assert_eq!(
+            quick("Pay rent tomorrow 9am #home"),
+            ("Pay rent #home".into(), day(10, 19), hour(9, 0))
+        );
+        assert_eq!(
+            quick("Pay rent on next fri 5:30pm"),
+            ("Pay rent".into(), day(10, 23), hour(17, 30))
+        );
+        assert_eq!(
+            quick("Call mom on sat"),
+            ("Call mom".into(), day(10, 24), None)
+        );
+        assert_eq!(
+            quick("Call mom sat"),
+            ("Call mom".into(), day(10, 24), None)
+        );
+        assert_eq!(
+            quick("Call mom sat noon #family"),
+            ("Call mom #family".into(), day(10, 24), hour(12, 0))
+        );
+        assert_eq!(
+            quick("Gym saturday evening"),
+            ("Gym evening".into(), day(10, 24), None)
+        );
+        assert_eq!(quick("Lunch at noon"), ("Lunch".into(), None, hour(12, 0)));
+    }
+
+    #[test]
+    fn keeps_title_words_that_look_like_dates() {
+        assert_eq!(quick("Buy sun cream"), ("Buy sun cream".into(), None, None));
+        assert_eq!(quick("Sat exam prep"), ("Sat exam prep".into(), None, None));
+        assert_eq!(
+            quick("Wed anniversary gift"),
+            ("Wed anniversary gift".into(), None, None)
+        );
+        assert_eq!(
+            quick("Write midnight story"),
+            ("Write midnight story".into(), None, None)
+        );
+        assert_eq!(quick("Read 2 books"), ("Read 2 books".into(), None, None));
+        // Only the first date is taken
+        assert_eq!(
+            quick("Move today to tomorrow"),
+            ("Move to tomorrow".into(), day(10, 18), None)
+        );
+    }
+}
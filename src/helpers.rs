@@ -4,6 +4,12 @@ use ratatui::{
     prelude::*,
     widgets::{Block, BorderType},
 };
+use std::{path::PathBuf, sync::OnceLock};
+
+// Set once from the command line ( or environment ) before anything is loaded
+static DATA_DIR: OnceLock<PathBuf> = OnceLock::new();
+static CONFIG_PATH: OnceLock<PathBuf> = OnceLock::new();
+static WORKSPACE: OnceLock<String> = OnceLock::new();
 
 pub enum PopupSize {
     Percentage { x: u16, y: u16 },
@@ -45,3 +51,28 @@ pub fn rounded_block(title: Line, border_style: Style) -> Block {
 pub fn get_project_dir() -> ProjectDirs {
     ProjectDirs::from("com", "CodeTrenchers", "TodoTUI").unwrap()
 }
+
+pub fn set_path_overrides(data_dir: Option<PathBuf>, config_path: Option<PathBuf>) {
+    if let Some(dir) = data_dir {
+        let _ = DATA_DIR.set(dir);
+    }
+    if let Some(path) = config_path {
+        let _ = CONFIG_PATH.set(path);
+    }
+}
+
+pub fn data_dir_override() -> Option<&'static PathBuf> {
+    DATA_DIR.get()
+}
+
+pub fn config_path_override() -> Option<&'static PathBuf> {
+    CONFIG_PATH.get()
+}
+
+pub fn set_workspace_override(name: String) {
+    let _ = WORKSPACE.set(name);
+}
+
+pub fn workspace_override() -> Option<&'static str> {
+    WORKSPACE.get().map(String::as_str)
+}
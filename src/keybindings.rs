@@ -0,0 +1,410 @@
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+/// Everything a key can be bound to, each part of the app only reacts to its own actions.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Quit,
+    SwitchFocus,
+    Up,
+    Down,
+    Left,
+    Right,
+    Deselect,
+    ToggleCompleted,
+    ToggleIcons,
+    NarrowList,
+    WidenList,
+    Delete,
+    Edit,
+    ToggleEncryption,
+    Preview,
+    Filter,
+    Sort,
+    Workspaces,
+    Themes,
+    Calendar,
+    Agenda,
+    Board,
+    MoveLeft,
+    MoveRight,
+    NewTask,
+    QuickAdd,
+    Search,
+    SaveSearch,
+    ToggleGroup,
+    ToggleAllGroups,
+    /// Opens the command line in vim mode
+    Command,
+    // Popups
+    Confirm,
+    Cancel,
+    Yes,
+    No,
+    PreviousPage,
+    NextPage,
+    // Task editor
+    Insert,
+    Save,
+    NormalMode,
+    NextField,
+    PreviousField,
+}
+
+/// A key with its modifiers, written like `ctrl-e`, `space` or `/` in the config.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(try_from = "String", into = "String")]
+pub struct KeyChord {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+enum Chords {
+    One(KeyChord),
+    Many(Vec<KeyChord>),
+}
+
+/// The `[keybindings]` table, actions that are not listed keep their default keys.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(transparent)]
+pub struct Keybindings {
+    bindings: BTreeMap<Action, Chords>,
+    /// Adds `hjkl` to the default arrow keys
+    #[serde(skip)]
+    pub vim: bool,
+}
+
+impl Keybindings {
+    pub fn is_empty(&self) -> bool {
+        self.bindings.is_empty()
+    }
+
+    fn chords(&self, action: Action) -> Vec<KeyChord> {
+        let vim = match action {
+            Action::Left if self.vim => Some("h"),
+            Action::Down if self.vim => Some("j"),
+            Action::Up if self.vim => Some("k"),
+            Action::Right if self.vim => Some("l"),
+            _ => None,
+        };
+        match self.bindings.get(&action) {
+            Some(Chords::One(chord)) => vec![*chord],
+            Some(Chords::Many(chords)) => chords.clone(),
+            None => default_chords(action)
+                .iter()
+                .chain(vim.as_ref())
+                .map(|chord| chord.parse().unwrap())
+                .collect(),
+        }
+    }
+
+    /// Returns the first of the actions that the key is bound to.
+    pub fn action(&self, key: &KeyEvent, actions: &[Action]) -> Option<Action> {
+        actions
+            .iter()
+            .copied()
+            .find(|action| self.chords(*action).iter().any(|chord| chord.matches(key)))
+    }
+
+    /// Footer hint like `[Esc/q] Cancel` listing the keys of all the actions.
+    pub fn hint(&self, actions: &[Action], label: &str) -> String {
+        let keys: Vec<String> = actions
+            .iter()
+            .flat_map(|action| self.chords(*action))
+            .map(|chord| chord.to_string())
+            .collect();
+        format!("[{}] {}", keys.join("/"), label)
+    }
+}
+
+// Names of the keys that aren't written as their character
+const KEY_NAMES: [(&str, KeyCode); 15] = [
+    ("space", KeyCode::Char(' ')),
+    ("enter", KeyCode::Enter),
+    ("esc", KeyCode::Esc),
+    ("tab", KeyCode::Tab),
+    ("backtab", KeyCode::BackTab),
+    ("backspace", KeyCode::Backspace),
+    ("delete", KeyCode::Delete),
+    ("up", KeyCode::Up),
+    ("down", KeyCode::Down),
+    ("left", KeyCode::Left),
+    ("right", KeyCode::Right),
+    ("home", KeyCode::Home),
+    ("end", KeyCode::End),
+    ("pageup", KeyCode::PageUp),
+    ("pagedown", KeyCode::PageDown),
+];
+
+fn default_chords(action: Action) -> &'static [&'static str] {
+    match action {
+        Action::Quit => &["q"],
+        Action::SwitchFocus => &["tab", "backtab"],
+        Action::Up => &["up"],
+        Action::Down => &["down"],
+        Action::Left => &["left"],
+        Action::Right => &["right"],
+        Action::Deselect => &["esc"],
+        Action::ToggleCompleted => &["space"],
+        Action::ToggleIcons => &["t"],
+        Action::NarrowList => &["["],
+        Action::WidenList => &["]"],
+        Action::Delete => &["d"],
+        Action::Edit => &["e"],
+        Action::ToggleEncryption => &["ctrl-e"],
+        Action::Preview => &["p"],
+        Action::Filter => &["f"],
+        Action::Sort => &["s"],
+        Action::Workspaces => &["w"],
+        Action::Themes => &["T"],
+        Action::Calendar => &["c"],
+        Action::Agenda => &["a"],
+        Action::Board => &["b"],
+        Action::MoveLeft => &["<"],
+        Action::MoveRight => &[">"],
+        Action::NewTask => &["n"],
+        Action::QuickAdd => &["A"],
+        Action::Search => &["/"],
+        Action::SaveSearch => &["S"],
+        Action::ToggleGroup => &["z"],
+        Action::ToggleAllGroups => &["Z"],
+        Action::Command => &[":"],
+        Action::Confirm => &["enter"],
+        Action::Cancel => &["esc"],
+        Action::Yes => &["y"],
+        Action::No => &["n"],
+        Action::PreviousPage => &["pageup"],
+        Action::NextPage => &["pagedown"],
+        Action::Insert => &["i"],
+        Action::Save => &["enter"],
+        Action::NormalMode => &["esc"],
+        Action::NextField => &["tab"],
+        Action::PreviousField => &["backtab"],
+    }
+}
+
+impl KeyChord {
+    fn matches(&self, key: &KeyEvent) -> bool {
+        // Shift is part of the character itself, e.g. `<` or `G`
+        let ignored = match key.code {
+            KeyCode::Char(_) | KeyCode::BackTab => KeyModifiers::SHIFT,
+            _ => KeyModifiers::NONE,
+        };
+        self.code == key.code && self.modifiers == key.modifiers.difference(ignored)
+    }
+}
+
+impl FromStr for KeyChord {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut key = text;
+        // A lone `-` is a key, not a separator
+        while let Some((modifier, rest)) = key.split_once('-').filter(|(_, rest)| !rest.is_empty())
+        {
+            modifiers |= match modifier.to_lowercase().as_str() {
+                "ctrl" | "c" => KeyModifiers::CONTROL,
+                "alt" | "a" => KeyModifiers::ALT,
+                "shift" | "s" => KeyModifiers::SHIFT,
+                _ => return Err(format!("Unknown modifier `{}` in `{}`", modifier, text)),
+            };
+            key = rest;
+        }
+
+        let named = KEY_NAMES
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(key))
+            .map(|(_, code)| *code);
+        let mut chars = key.chars();
+        let code = match (named, chars.next(), chars.next()) {
+            (Some(code), _, _) => code,
+            (None, Some(c), None) => KeyCode::Char(c),
+            _ => return Err(format!("Unknown key `{}`", text)),
+        };
+        // Shift is part of the character, `shift-g` is `G`. Other shifted characters depend on the
+        // keyboard layout, they have to be written as the character itself
+        if let KeyCode::Char(c) = code
+            && modifiers.contains(KeyModifiers::SHIFT)
+        {
+            if !c.is_alphabetic() {
+                return Err(format!(
+                    "Write `{}` as the shifted character itself, like `<` or `!`",
+                    text
+                ));
+            }
+            modifiers.remove(KeyModifiers::SHIFT);
+            return Ok(Self {
+                code: KeyCode::Char(c.to_uppercase().next().unwrap_or(c)),
+                modifiers,
+            });
+        }
+        // Terminals report shift-tab as its own key
+        if code == KeyCode::Tab && modifiers.contains(KeyModifiers::SHIFT) {
+            modifiers.remove(KeyModifiers::SHIFT);
+            return Ok(Self {
+                code: KeyCode::BackTab,
+                modifiers,
+            });
+        }
+        Ok(Self { code, modifiers })
+    }
+}
+
+impl TryFrom<String> for KeyChord {
+    type Error = String;
+
+    fn try_from(text: String) -> Result<Self, Self::Error> {
+        text.parse()
+    }
+}
+
+impl From<KeyChord> for String {
+    fn from(chord: KeyChord) -> Self {
+        let mut text = String::new();
+        for (modifier, name) in [
+            (KeyModifiers::CONTROL, "ctrl-"),
+            (KeyModifiers::ALT, "alt-"),
+            (KeyModifiers::SHIFT, "shift-"),
+        ] {
+            if chord.modifiers.contains(modifier) {
+                text.push_str(name);
+            }
+        }
+        match (
+            KEY_NAMES.iter().find(|(_, code)| *code == chord.code),
+            chord.code,
+        ) {
+            (Some((name, _)), _) => text.push_str(name),
+            (None, KeyCode::Char(c)) => text.push(c),
+            _ => {}
+        }
+        text
+    }
+}
+
+// Short form for the footer, e.g. `C-e`
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (modifier, name) in [
+            (KeyModifiers::CONTROL, "C-"),
+            (KeyModifiers::ALT, "A-"),
+            (KeyModifiers::SHIFT, "S-"),
+        ] {
+            if self.modifiers.contains(modifier) {
+                write!(f, "{}", name)?;
+            }
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::Enter => write!(f, "Enter"),
+            KeyCode::Esc => write!(f, "Esc"),
+            KeyCode::Tab => write!(f, "Tab"),
+            KeyCode::BackTab => write!(f, "S-Tab"),
+            KeyCode::PageUp => write!(f, "PgUp"),
+            KeyCode::PageDown => write!(f, "PgDn"),
+            code => {
+                // Capitalized name, e.g. `Up`
+                let name = String::from(KeyChord {
+                    code,
+                    modifiers: KeyModifiers::NONE,
+                });
+                let mut chars = name.chars();
+                let first = chars.next().map(|c| c.to_ascii_uppercase());
+                write!(f, "{}{}", first.unwrap_or_default(), chars.as_str())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chord(text: &str) -> (KeyCode, KeyModifiers) {
+        let chord: KeyChord = text.parse().unwrap();
+        (chord.code, chord.modifiers)
+    }
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn parses_chords() {
+        assert_eq!(chord("ctrl-e"), (KeyCode::Char('e'), KeyModifiers::CONTROL));
+        assert_eq!(chord("C-A-x").1, KeyModifiers::CONTROL | KeyModifiers::ALT);
+        assert_eq!(chord("Space"), (KeyCode::Char(' '), KeyModifiers::NONE));
+        assert_eq!(chord("-"), (KeyCode::Char('-'), KeyModifiers::NONE));
+        assert_eq!(chord("ctrl--"), (KeyCode::Char('-'), KeyModifiers::CONTROL));
+        assert_eq!(chord("shift-tab"), (KeyCode::BackTab, KeyModifiers::NONE));
+    }
+
+    #[test]
+    fn reads_shifted_letters_as_uppercase() {
+        assert_eq!(chord("shift-x"), (KeyCode::Char('X'), KeyModifiers::NONE));
+        assert_eq!(
+            chord("ctrl-shift-x"),
+            (KeyCode::Char('X'), KeyModifiers::CONTROL)
+        );
+        assert!("shift-1".parse::<KeyChord>().is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_keys() {
+        assert_eq!(
+            "hyper-x".parse::<KeyChord>().err(),
+            Some("Unknown modifier `hyper` in `hyper-x`".into())
+        );
+        assert_eq!(
+            "ctrl-foo".parse::<KeyChord>().err(),
+            Some("Unknown key `ctrl-foo`".into())
+        );
+    }
+
+    #[test]
+    fn writes_chords_back() {
+        for text in ["ctrl-e", "space", "G", "alt-pageup", "<"] {
+            let chord: KeyChord = text.parse().unwrap();
+            assert_eq!(String::from(chord), text);
+        }
+        let chord: KeyChord = "ctrl-e".parse().unwrap();
+        assert_eq!(chord.to_string(), "C-e");
+    }
+
+    #[test]
+    fn matches_keys_with_shift_in_the_character() {
+        let keys = Keybindings::default();
+        let upper = key(KeyCode::Char('T'), KeyModifiers::SHIFT);
+        assert!(keys.action(&upper, &[Action::Themes]).is_some());
+        let lower = key(KeyCode::Char('t'), KeyModifiers::NONE);
+        assert!(keys.action(&lower, &[Action::Themes]).is_none());
+        let ctrl = key(KeyCode::Char('e'), KeyModifiers::CONTROL);
+        assert!(keys.action(&ctrl, &[Action::Edit]).is_none());
+    }
+
+    #[test]
+    fn configured_keys_replace_the_defaults() {
+        let mut keys: Keybindings = toml::from_str("quit = [\"x\", \"ctrl-c\"]").unwrap();
+        let quit = [Action::Quit];
+        assert!(
+            keys.action(&key(KeyCode::Char('x'), KeyModifiers::NONE), &quit)
+                .is_some()
+        );
+        assert!(
+            keys.action(&key(KeyCode::Char('q'), KeyModifiers::NONE), &quit)
+                .is_none()
+        );
+        assert_eq!(keys.hint(&quit, "Quit"), "[x/C-c] Quit");
+
+        let j = key(KeyCode::Char('j'), KeyModifiers::NONE);
+        assert!(keys.action(&j, &[Action::Down]).is_none());
+        keys.vim = true;
+        assert!(keys.action(&j, &[Action::Down]).is_some());
+    }
+}
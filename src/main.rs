@@ -1,38 +1,76 @@
 use ratatui::{
     DefaultTerminal,
-    crossterm::event::{self, Event, KeyEventKind},
+    crossterm::{
+        event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyEventKind},
+        execute,
+    },
+};
+use std::{
+    io::{Result, stdout},
+    time::Duration,
 };
-use std::io::Result;
 use ui::App;
 
 mod auth;
 mod cli;
 mod config;
+mod dates;
 mod helpers;
+mod keybindings;
+mod merge;
+mod search;
+mod state;
+mod storage;
+mod sync;
 mod tasks;
+mod terminal;
 mod theme;
 mod ui;
+mod watcher;
+
+// How long to wait for a key press before checking the task file for changes
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
 
 fn main() -> Result<()> {
     cli::handle_arguments()?;
-    let mut terminal = ratatui::init();
     let config = crate::config::Config::load();
-    let app_result = run(&mut terminal, App::new(!config.exists(), config));
+    // Remember the workspace picked on the command line as the last used one
+    if config.exists() && crate::helpers::workspace_override().is_some() {
+        config.save();
+    }
+    // Loaded before taking over the terminal so that errors stay readable
+    let mut app = match App::new(!config.exists(), config) {
+        Ok(app) => app,
+        Err(error) => {
+            eprintln!("{}", error);
+            std::process::exit(1);
+        }
+    };
+    let mut terminal = ratatui::init();
+    execute!(stdout(), EnableMouseCapture)?;
+    let app_result = run(&mut terminal, &mut app);
+    let _ = execute!(stdout(), DisableMouseCapture);
     ratatui::restore();
+    app.save_state();
     app_result
 }
 
-fn run(terminal: &mut DefaultTerminal, mut app: App) -> Result<()> {
+fn run(terminal: &mut DefaultTerminal, app: &mut App) -> Result<()> {
     loop {
         terminal.draw(|frame| app.draw(frame, frame.area()))?;
-        if let Event::Key(key) = event::read()? {
-            if key.kind != KeyEventKind::Press && key.kind != KeyEventKind::Repeat {
-                continue;
-            }
-            if app.handle_key(key) {
-                break;
+        if event::poll(POLL_INTERVAL)? {
+            match event::read()? {
+                Event::Key(key)
+                    if matches!(key.kind, KeyEventKind::Press | KeyEventKind::Repeat)
+                        && app.handle_key(key) =>
+                {
+                    break;
+                }
+                Event::Mouse(mouse) => app.handle_mouse(mouse),
+                _ => {}
             }
         }
+        app.reload_if_changed();
     }
     Ok(())
 }
@@ -0,0 +1,271 @@
+use std::{collections::HashMap, fmt};
+
+use crate::tasks::Task;
+
+/// Titles of the tasks touched by a merge, per kind of change to the local list.
+#[derive(Default)]
+pub struct Report {
+    pub added: Vec<String>,
+    pub updated: Vec<String>,
+    pub conflicting: Vec<String>,
+    pub deleted: Vec<String>,
+}
+
+// Whether merging a task took any value from the other side, and which fields conflicted
+#[derive(Default)]
+struct Outcome {
+    updated: bool,
+    conflicts: Vec<&'static str>,
+}
+
+/// Merges another copy of the task list into the local one, task by task and field by field.
+///
+/// With a common ancestor a field changed on only one side takes that side's value and a task
+/// deleted on one side is deleted unless the other side edited it. A field changed on both sides
+/// is a conflict and the most recently modified value wins. Without an ancestor every differing
+/// field is decided by its modification time and nothing is deleted, a tie keeps the local value
+/// and is reported as a conflict.
+pub fn merge(base: Option<&[Task]>, local: &[Task], other: &[Task]) -> (Vec<Task>, Report) {
+    let base: Option<HashMap<u128, &Task>> =
+        base.map(|tasks| tasks.iter().map(|task| (task.id, task)).collect());
+    let base_task = |id: u128| base.as_ref().and_then(|base| base.get(&id).copied());
+    let other_map: HashMap<u128, &Task> = other.iter().map(|task| (task.id, task)).collect();
+    let local_map: HashMap<u128, &Task> = local.iter().map(|task| (task.id, task)).collect();
+
+    let mut report = Report::default();
+    let mut merged = Vec::new();
+    for task in local {
+        let Some(other_task) = other_map.get(&task.id) else {
+            // Deleted on the other side, kept only if it was edited here
+            if base_task(task.id).is_some_and(|base_task| base_task == task) {
+                report.deleted.push(task.title.clone());
+            } else {
+                merged.push(task.clone());
+            }
+            continue;
+        };
+
+        let (task, outcome) = merge_task(base_task(task.id), task, other_task);
+        if !outcome.conflicts.is_empty() {
+            let fields = outcome.conflicts.join(", ");
+            report
+                .conflicting
+                .push(format!("{} ({})", task.title, fields));
+        } else if outcome.updated {
+            report.updated.push(task.title.clone());
+        }
+        merged.push(task);
+    }
+
+    for task in other {
+        if local_map.contains_key(&task.id) {
+            continue;
+        }
+        // Deleted here, kept only if it was edited on the other side
+        if !base_task(task.id).is_some_and(|base_task| base_task == task) {
+            report.added.push(task.title.clone());
+            merged.push(task.clone());
+        }
+    }
+    (merged, report)
+}
+
+fn merge_task(base: Option<&Task>, local: &Task, other: &Task) -> (Task, Outcome) {
+    let mut task = local.clone();
+    let mut outcome = Outcome::default();
+    let times = &mut task.modified;
+
+    merge_field(
+        "title",
+        base.map(|base| &base.title),
+        (&mut task.title, &mut times.title),
+        (&other.title, other.modified.title),
+        PartialEq::eq,
+        &mut outcome,
+    );
+    merge_field(
+        "date",
+        base.map(|base| &base.date),
+        (&mut task.date, &mut times.date),
+        (&other.date, other.modified.date),
+        PartialEq::eq,
+        &mut outcome,
+    );
+    merge_field(
+        "time",
+        base.map(|base| &base.time),
+        (&mut task.time, &mut times.time),
+        (&other.time, other.modified.time),
+        PartialEq::eq,
+        &mut outcome,
+    );
+    merge_field(
+        "description",
+        base.map(|base| &base.description),
+        (&mut task.description, &mut times.description),
+        (&other.description, other.modified.description),
+        PartialEq::eq,
+        &mut outcome,
+    );
+    merge_field(
+        "status",
+        base.map(|base| &base.status),
+        (&mut task.status, &mut times.status),
+        (&other.status, other.modified.status),
+        PartialEq::eq,
+        &mut outcome,
+    );
+    if task.status.is_done() != local.status.is_done() {
+        task.completed_at = other.completed_at;
+    }
+    task.updated_at = task.updated_at.max(other.updated_at);
+    (task, outcome)
+}
+
+fn merge_field<T: Clone>(
+    name: &'static str,
+    base: Option<&T>,
+    (local, local_time): (&mut T, &mut i64),
+    (other, other_time): (&T, i64),
+    same: impl Fn(&T, &T) -> bool,
+    outcome: &mut Outcome,
+) {
+    if same(local, other) {
+        return;
+    }
+    let local_changed = base.is_none_or(|base| !same(base, local));
+    let other_changed = base.is_none_or(|base| !same(base, other));
+    let take_other = match (local_changed, other_changed) {
+        (false, true) => true,
+        (true, false) => false,
+        _ => {
+            if base.is_some() || other_time == *local_time {
+                outcome.conflicts.push(name);
+            }
+            other_time > *local_time
+        }
+    };
+    if take_other {
+        *local = other.clone();
+        *local_time = other_time;
+        outcome.updated = true;
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let sections = [
+            ("Added", "+", &self.added),
+            ("Updated", "~", &self.updated),
+            ("Conflicting", "!", &self.conflicting),
+            ("Deleted", "-", &self.deleted),
+        ];
+        for (title, marker, tasks) in sections {
+            writeln!(f, "{} ({}):", title, tasks.len())?;
+            for task in tasks {
+                writeln!(f, "  {} {}", marker, task)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tasks::TaskStatus;
+
+    fn task(id: u128, title: &str, modified: i64) -> Task {
+        let mut task = Task::from(id);
+        task.title = title.to_string();
+        task.modified.title = modified;
+        task
+    }
+
+    fn titles(tasks: &[Task]) -> Vec<&str> {
+        tasks.iter().map(|task| task.title.as_str()).collect()
+    }
+
+    #[test]
+    fn takes_fields_changed_on_one_side() {
+        crate::tasks::set_test_statuses();
+        let base = vec![task(1, "Rent", 1)];
+        let mut local = base.clone();
+        local[0].description = "Before the 5th".into();
+        local[0].modified.description = 2;
+        let mut other = base.clone();
+        other[0].title = "Pay rent".into();
+        other[0].modified.title = 3;
+
+        let (merged, report) = merge(Some(&base), &local, &other);
+        assert_eq!(titles(&merged), ["Pay rent"]);
+        assert_eq!(merged[0].description, "Before the 5th");
+        assert_eq!(merged[0].modified.title, 3);
+        assert_eq!(report.updated, ["Pay rent"]);
+        assert!(report.conflicting.is_empty());
+    }
+
+    #[test]
+    fn latest_change_wins_a_conflict() {
+        crate::tasks::set_test_statuses();
+        let base = vec![task(1, "Rent", 1)];
+        let local = vec![task(1, "Pay rent", 3)];
+        let other = vec![task(1, "Rent due", 2)];
+
+        let (merged, report) = merge(Some(&base), &local, &other);
+        assert_eq!(titles(&merged), ["Pay rent"]);
+        assert_eq!(report.conflicting, ["Pay rent (title)"]);
+
+        let (merged, _) = merge(Some(&base), &other, &local);
+        assert_eq!(titles(&merged), ["Pay rent"]);
+    }
+
+    #[test]
+    fn deletes_unless_edited_on_the_other_side() {
+        crate::tasks::set_test_statuses();
+        let base = vec![task(1, "Rent", 1), task(2, "Gym", 1)];
+        let local = vec![task(1, "Rent", 1), task(2, "Gym at 6", 2)];
+        let other = vec![task(3, "Call mom", 1)];
+
+        let (merged, report) = merge(Some(&base), &local, &other);
+        assert_eq!(titles(&merged), ["Gym at 6", "Call mom"]);
+        assert_eq!(report.deleted, ["Rent"]);
+        assert_eq!(report.added, ["Call mom"]);
+
+        // Deleted here and untouched there stays deleted
+        let (merged, report) = merge(Some(&base), &other, &base);
+        assert_eq!(titles(&merged), ["Call mom"]);
+        assert!(report.added.is_empty());
+    }
+
+    #[test]
+    fn uses_modification_times_without_an_ancestor() {
+        crate::tasks::set_test_statuses();
+        let local = vec![task(1, "Rent", 2), task(2, "Gym", 1)];
+        let other = vec![task(1, "Pay rent", 1), task(2, "Gym at 6", 1)];
+
+        let (merged, report) = merge(None, &local, &other);
+        assert_eq!(titles(&merged), ["Rent", "Gym"]);
+        assert!(report.deleted.is_empty());
+        // Only the tie is a conflict
+        assert_eq!(report.conflicting, ["Gym (title)"]);
+
+        let (merged, report) = merge(None, &[], &other);
+        assert_eq!(titles(&merged), ["Pay rent", "Gym at 6"]);
+        assert!(report.deleted.is_empty());
+    }
+
+    #[test]
+    fn takes_the_completion_time_with_the_status() {
+        crate::tasks::set_test_statuses();
+        let base = vec![task(1, "Rent", 1)];
+        let mut other = base.clone();
+        other[0].status = TaskStatus::done();
+        other[0].modified.status = 2;
+        other[0].completed_at = Some(2);
+
+        let (merged, _) = merge(Some(&base), &base, &other);
+        assert!(merged[0].status.is_done());
+        assert_eq!(merged[0].completed_at, Some(2));
+    }
+}
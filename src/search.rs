@@ -0,0 +1,373 @@
+use chrono::NaiveDate;
+use fuzzy_matcher::{FuzzyMatcher, skim::SkimMatcherV2};
+
+use crate::{dates::Due, tasks::Task};
+
+/// A parsed search like `status:pending due:<friday "exact phrase" -excluded`, tasks have to
+/// match every term.
+pub struct Search {
+    terms: Vec<Term>,
+    matcher: SkimMatcherV2,
+}
+
+/// How well a task matched the words of a search.
+pub struct SearchMatch {
+    pub score: i64,
+    /// Positions of the matched characters in the title
+    pub title: Vec<usize>,
+}
+
+struct Term {
+    /// Written with a leading `-`, matching tasks are left out
+    negated: bool,
+    kind: TermKind,
+}
+
+enum TermKind {
+    /// Fuzzy matched against the title and the description
+    Word(String),
+    /// Lowercase, found anywhere in the title or the description
+    Phrase(String),
+    /// Name of a configured status
+    Status(String),
+    Overdue,
+    /// Inclusive range, open on the missing ends
+    Date(DateField, Option<NaiveDate>, Option<NaiveDate>),
+}
+
+#[derive(Clone, Copy)]
+enum DateField {
+    Due,
+    Created,
+    Updated,
+    Completed,
+}
+
+impl Search {
+    /// Relative dates like `today` or `friday` are resolved against `today`.
+    pub fn parse(text: &str, today: NaiveDate) -> Result<Self, String> {
+        let terms = match Self::parse_legacy(text) {
+            Some(term) => vec![term],
+            None => Self::tokenize(text)?
+                .iter()
+                .map(|token| Self::parse_term(token, today))
+                .collect::<Result<_, _>>()?,
+        };
+        Ok(Self {
+            terms,
+            // Smart case, lowercase words match any case
+            matcher: SkimMatcherV2::default().smart_case(),
+        })
+    }
+
+    /// `None` when the task doesn't match, the fuzzy words decide the score.
+    pub fn matches(&self, task: &Task) -> Option<SearchMatch> {
+        let mut found = SearchMatch {
+            score: 0,
+            title: Vec::new(),
+        };
+        for term in &self.terms {
+            let matched = match &term.kind {
+                TermKind::Word(word) => {
+                    let title = self.matcher.fuzzy_indices(&task.title, word);
+                    let description = self.matcher.fuzzy_match(&task.description, word);
+                    let score = title.as_ref().map(|(score, _)| *score).max(description);
+                    if let Some(score) = score {
+                        found.score += score;
+                        found
+                            .title
+                            .extend(title.into_iter().flat_map(|(_, indices)| indices));
+                    }
+                    score.is_some()
+                }
+                TermKind::Phrase(phrase) => {
+                    task.title.to_lowercase().contains(phrase)
+                        || task.description.to_lowercase().contains(phrase)
+                }
+                TermKind::Status(name) => task.status.as_str() == name,
+                TermKind::Overdue => task.is_overdue(),
+                TermKind::Date(field, from, to) => field.date(task).is_some_and(|date| {
+                    from.is_none_or(|from| date >= from) && to.is_none_or(|to| date <= to)
+                }),
+            };
+            if matched == term.negated {
+                return None;
+            }
+        }
+        found.title.sort_unstable();
+        found.title.dedup();
+        Some(found)
+    }
+
+    // The older `DD MM YYYY - DD MM YYYY` and `created:DD MM YYYY` searches keep working
+    fn parse_legacy(text: &str) -> Option<Term> {
+        let (field, dates) = match text.split_once(':') {
+            Some((field, dates)) => (DateField::from_name(field.trim())?, dates),
+            None => (DateField::Due, text),
+        };
+        let parse = |date: &str| NaiveDate::parse_from_str(date.trim(), "%d %m %Y").ok();
+        let (from, to) = match dates.split_once(" - ") {
+            Some((from, to)) => (parse(from)?, parse(to)?),
+            None => (parse(dates)?, parse(dates)?),
+        };
+        Some(Term {
+            negated: false,
+            kind: TermKind::Date(field, Some(from), Some(to)),
+        })
+    }
+
+    // Splits at spaces outside of quotes, the quotes are kept to tell phrases from words
+    fn tokenize(text: &str) -> Result<Vec<String>, String> {
+        let mut tokens = Vec::new();
+        let mut token = String::new();
+        let mut quoted = false;
+        for c in text.chars() {
+            match c {
+                '"' => {
+                    quoted = !quoted;
+                    token.push(c);
+                }
+                c if c.is_whitespace() && !quoted => {
+                    if !token.is_empty() {
+                        tokens.push(std::mem::take(&mut token));
+                    }
+                }
+                c => token.push(c),
+            }
+        }
+        if quoted {
+            return Err("Missing closing quote".into());
+        }
+        if !token.is_empty() {
+            tokens.push(token);
+        }
+        Ok(tokens)
+    }
+
+    fn parse_term(token: &str, today: NaiveDate) -> Result<Term, String> {
+        let (negated, token) = match token.strip_prefix('-') {
+            Some(rest) if !rest.is_empty() => (true, rest),
+            _ => (false, token),
+        };
+        let unquote = |text: &str| Some(text.strip_prefix('"')?.strip_suffix('"')?.to_string());
+
+        let kind = if let Some(phrase) = unquote(token) {
+            TermKind::Phrase(phrase.to_lowercase())
+        } else if let Some((field, value)) = token.split_once(':') {
+            let value = unquote(value).unwrap_or_else(|| value.to_string());
+            if value.is_empty() {
+                return Err(format!("Missing value after `{}:`", field));
+            }
+            match field.to_lowercase().as_str() {
+                "status" => Self::parse_status(&value)?,
+                name => match DateField::from_name(name) {
+                    Some(field) => {
+                        let (from, to) = Self::parse_range(&value, today)?;
+                        TermKind::Date(field, from, to)
+                    }
+                    None => {
+                        return Err(format!(
+                            "Unknown field `{}`, use status, due, created, updated or completed",
+                            field
+                        ));
+                    }
+                },
+            }
+        } else if negated {
+            // Leaving out fuzzy matches would hide far more than expected
+            TermKind::Phrase(token.to_lowercase())
+        } else {
+            TermKind::Word(token.to_string())
+        };
+        Ok(Term { negated, kind })
+    }
+
+    fn parse_status(name: &str) -> Result<TermKind, String> {
+        if name.eq_ignore_ascii_case("overdue") {
+            return Ok(TermKind::Overdue);
+        }
+        crate::tasks::statuses()
+            .iter()
+            .find(|status| status.name.eq_ignore_ascii_case(name))
+            .map(|status| TermKind::Status(status.name.clone()))
+            .ok_or_else(|| format!("Unknown status `{}`", name))
+    }
+
+    // `DATE`, `<DATE`, `<=DATE`, `>DATE`, `>=DATE` or `DATE..DATE` with either end left out
+    fn parse_range(
+        value: &str,
+        today: NaiveDate,
+    ) -> Result<(Option<NaiveDate>, Option<NaiveDate>), String> {
+        if let Some((from, to)) = value.split_once("..") {
+            let parse = |date: &str| match date {
+                "" => Ok(None),
+                date => Self::parse_date(date, today).map(Some),
+            };
+            return Ok((parse(from)?, parse(to)?));
+        }
+        let (operator, date) = ["<=", ">=", "<", ">"]
+            .into_iter()
+            .find_map(|operator| Some((operator, value.strip_prefix(operator)?)))
+            .unwrap_or(("", value));
+        let date = Self::parse_date(date, today)?;
+        Ok(match operator {
+            "<" => (None, date.pred_opt()),
+            "<=" => (None, Some(date)),
+            ">" => (date.succ_opt(), None),
+            ">=" => (Some(date), None),
+            _ => (Some(date), Some(date)),
+        })
+    }
+
+    // The same phrases as in the task editor, without a time
+    fn parse_date(text: &str, today: NaiveDate) -> Result<NaiveDate, String> {
+        Due::parse(text, today)
+            .filter(|due| due.time.is_none())
+            .and_then(|due| due.date)
+            .ok_or_else(|| {
+                format!(
+                    "Not a date `{}`, use YYYY-MM-DD, today, a weekday or `in 3 days`",
+                    text
+                )
+            })
+    }
+}
+
+impl DateField {
+    fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "due" => Some(Self::Due),
+            "created" => Some(Self::Created),
+            "updated" => Some(Self::Updated),
+            "completed" => Some(Self::Completed),
+            _ => None,
+        }
+    }
+
+    fn date(self, task: &Task) -> Option<NaiveDate> {
+        let timestamp = match self {
+            Self::Due => return task.day(),
+            Self::Created => Some(task.created_at),
+            Self::Updated => Some(task.updated_at),
+            Self::Completed => task.completed_at,
+        };
+        // Zero means the task is older than the timestamps
+        timestamp
+            .filter(|millis| *millis != 0)
+            .and_then(crate::tasks::local_time)
+            .map(|time| time.date())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tasks::TaskStatus;
+
+    // A Sunday
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, 18).unwrap()
+    }
+
+    fn task(title: &str, date: &str, status: &str) -> Task {
+        let mut task = Task::from(1);
+        task.title = title.to_string();
+        task.date = date.to_string();
+        task.status = TaskStatus::from(status.to_string());
+        task
+    }
+
+    fn matches(search: &str, task: &Task) -> bool {
+        Search::parse(search, today())
+            .unwrap()
+            .matches(task)
+            .is_some()
+    }
+
+    #[test]
+    fn matches_words_and_phrases() {
+        crate::tasks::set_test_statuses();
+        let task = task("Pay the rent", "", "Todo");
+        assert!(matches("rent", &task));
+        assert!(matches("pyrnt", &task));
+        assert!(matches("\"the rent\"", &task));
+        assert!(!matches("\"pay rent\"", &task));
+        assert!(!matches("-rent", &task));
+        assert!(matches("-\"call mom\" pay", &task));
+    }
+
+    #[test]
+    fn highlights_the_matched_title_characters() {
+        crate::tasks::set_test_statuses();
+        let found = Search::parse("pay", today())
+            .unwrap()
+            .matches(&task("Pay the rent", "", "Todo"))
+            .unwrap();
+        assert_eq!(found.title, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn filters_by_status() {
+        crate::tasks::set_test_statuses();
+        let doing = task("Write report", "", "Doing");
+        assert!(matches("status:doing", &doing));
+        assert!(!matches("status:done", &doing));
+        assert!(matches("-status:done report", &doing));
+        assert!(!matches("status:overdue", &doing));
+        assert!(matches(
+            "status:overdue",
+            &task("Late", "01 01 2020", "Todo")
+        ));
+        assert!(!matches(
+            "status:overdue",
+            &task("Late", "01 01 2020", "Done")
+        ));
+    }
+
+    #[test]
+    fn filters_by_due_date() {
+        crate::tasks::set_test_statuses();
+        let friday = task("Report", "23 10 2026", "Todo");
+        assert!(matches("due:friday", &friday));
+        assert!(matches("due:2026-10-23", &friday));
+        assert!(matches("due:<=friday", &friday));
+        assert!(!matches("due:<friday", &friday));
+        assert!(matches("due:>today", &friday));
+        assert!(matches("due:\"in 5 days\"", &friday));
+        assert!(matches("due:today..2026-10-31", &friday));
+        assert!(matches("due:..friday", &friday));
+        assert!(!matches("due:tomorrow", &friday));
+        assert!(!matches("due:>=today", &task("Someday", "", "Todo")));
+    }
+
+    #[test]
+    fn keeps_the_older_date_searches() {
+        crate::tasks::set_test_statuses();
+        let friday = task("Report", "23 10 2026", "Todo");
+        assert!(matches("20 10 2026 - 25 10 2026", &friday));
+        assert!(matches("23 10 2026", &friday));
+        assert!(!matches("24 10 2026", &friday));
+    }
+
+    #[test]
+    fn reports_mistakes() {
+        crate::tasks::set_test_statuses();
+        let error = |text| Search::parse(text, today()).err();
+        assert_eq!(error("\"open"), Some("Missing closing quote".into()));
+        assert_eq!(
+            error("status:"),
+            Some("Missing value after `status:`".into())
+        );
+        assert_eq!(
+            error("status:waiting"),
+            Some("Unknown status `waiting`".into())
+        );
+        assert!(
+            error("owner:me")
+                .unwrap()
+                .starts_with("Unknown field `owner`")
+        );
+        assert!(error("due:soon").unwrap().starts_with("Not a date `soon`"));
+        assert!(error("due:5pm").is_some());
+    }
+}
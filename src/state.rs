@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// What the UI looked like when the app was closed, restored on the next start.
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiState {
+    /// Picked in the theme picker, takes precedence over the theme of the config
+    pub theme: Option<String>,
+    pub ascii_icons: bool,
+    /// Name of the filter option, a status, `Overdue` or `None` for all tasks
+    pub filter: Option<String>,
+    pub search: String,
+    pub selected: Option<u128>,
+    /// Width of the task list in percent of the screen
+    pub list_width: u16,
+}
+
+impl Default for UiState {
+    fn default() -> Self {
+        Self {
+            theme: None,
+            ascii_icons: false,
+            filter: None,
+            search: String::new(),
+            selected: None,
+            list_width: 50,
+        }
+    }
+}
+
+impl UiState {
+    // The state is a convenience, a missing or broken file just starts with the defaults
+    pub fn load(data_dir: &Path) -> Self {
+        fs::read_to_string(Self::get_path(data_dir))
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, data_dir: &Path) {
+        let path = Self::get_path(data_dir);
+        if let (Some(dir), Ok(data)) = (path.parent(), serde_json::to_string_pretty(self)) {
+            let _ = fs::create_dir_all(dir).and_then(|_| fs::write(&path, data));
+        }
+    }
+
+    fn get_path(data_dir: &Path) -> PathBuf {
+        data_dir.join("state.json")
+    }
+}
@@ -0,0 +1,55 @@
+use std::{fs, io, path::PathBuf};
+
+use super::Storage;
+use crate::tasks::Task;
+
+/// Stores the whole list in a single pretty printed JSON file, or an encrypted blob.
+pub struct JsonStorage {
+    dir: PathBuf,
+    encrypted: bool,
+}
+
+impl JsonStorage {
+    pub fn new(dir: PathBuf, encrypted: bool) -> Self {
+        Self { dir, encrypted }
+    }
+
+    /// Parses the contents of a task file, e.g. an older version of it.
+    pub fn decode(&self, data: &[u8]) -> io::Result<Vec<Task>> {
+        if self.encrypted {
+            crate::auth::decrypt_tasks(data)
+        } else {
+            Ok(serde_json::from_slice(data)?)
+        }
+    }
+
+    pub fn encode(&self, tasks: &[Task]) -> io::Result<Vec<u8>> {
+        if self.encrypted {
+            Ok(crate::auth::encrypt_tasks(tasks))
+        } else {
+            Ok(serde_json::to_vec_pretty(tasks)?)
+        }
+    }
+}
+
+impl Storage for JsonStorage {
+    fn load(&self) -> io::Result<Vec<Task>> {
+        match fs::read(self.path()) {
+            Ok(data) => self.decode(&data),
+            Err(_) => Ok(Vec::new()),
+        }
+    }
+
+    fn save(&self, tasks: &[Task]) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        fs::write(self.path(), self.encode(tasks)?)
+    }
+
+    fn path(&self) -> PathBuf {
+        if self.encrypted {
+            self.dir.join("tasks.enc")
+        } else {
+            self.dir.join("tasks.json")
+        }
+    }
+}
@@ -0,0 +1,84 @@
+mod json;
+mod sqlite;
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use std::{io, path::PathBuf};
+
+use crate::{
+    config::Config,
+    tasks::{Task, TaskStatus},
+};
+
+pub use json::JsonStorage;
+pub use sqlite::SqliteStorage;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    #[default]
+    Json,
+    Sqlite,
+}
+
+/// Filters a task query, empty fields match every task.
+#[derive(Default)]
+pub struct Query {
+    pub statuses: Vec<TaskStatus>,
+    pub due_from: Option<NaiveDateTime>,
+    pub due_to: Option<NaiveDateTime>,
+}
+
+pub trait Storage {
+    fn load(&self) -> io::Result<Vec<Task>>;
+
+    /// Replaces the whole stored list.
+    fn save(&self, tasks: &[Task]) -> io::Result<()>;
+
+    /// Stores a single added or modified task, `tasks` is the full list after the change.
+    fn save_task(&self, tasks: &[Task], _task: &Task) -> io::Result<()> {
+        self.save(tasks)
+    }
+
+    /// Removes a single task, `tasks` is the full list after the removal.
+    fn delete_task(&self, tasks: &[Task], _id: u128) -> io::Result<()> {
+        self.save(tasks)
+    }
+
+    fn query(&self, query: &Query) -> io::Result<Vec<Task>> {
+        let mut tasks = self.load()?;
+        tasks.retain(|task| query.matches(task));
+        Ok(tasks)
+    }
+
+    /// The file holding the tasks.
+    fn path(&self) -> PathBuf;
+}
+
+impl Query {
+    pub fn matches(&self, task: &Task) -> bool {
+        let due = task.due();
+        (self.statuses.is_empty() || self.statuses.contains(&task.status))
+            && self
+                .due_from
+                .is_none_or(|from| due.is_some_and(|due| due >= from))
+            && self
+                .due_to
+                .is_none_or(|to| due.is_some_and(|due| due <= to))
+    }
+}
+
+pub fn open(config: &Config) -> io::Result<Box<dyn Storage>> {
+    open_backend(config.data_dir(), config.backend(), config.encryption())
+}
+
+pub fn open_backend(
+    dir: PathBuf,
+    backend: Backend,
+    encrypted: bool,
+) -> io::Result<Box<dyn Storage>> {
+    Ok(match backend {
+        Backend::Json => Box::new(JsonStorage::new(dir, encrypted)),
+        Backend::Sqlite => Box::new(SqliteStorage::open(dir, encrypted)?),
+    })
+}
@@ -0,0 +1,228 @@
+use rusqlite::{Connection, ToSql, params};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    slice,
+};
+
+use super::{Query, Storage};
+use crate::tasks::{Task, TaskStatus};
+
+// Due dates are stored in a sortable format so that range queries can use the index
+const DUE_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/// Stores one row per task, so a change only writes the affected task.
+///
+/// The due date and status are kept in plain indexed columns for querying, the task itself is
+/// stored as JSON which is encrypted when encryption is enabled.
+pub struct SqliteStorage {
+    dir: PathBuf,
+    encrypted: bool,
+    connection: Connection,
+}
+
+impl SqliteStorage {
+    pub fn open(dir: PathBuf, encrypted: bool) -> io::Result<Self> {
+        fs::create_dir_all(&dir)?;
+        let path = Self::file_path(&dir, encrypted);
+        let connection = Connection::open(path).map_err(io::Error::other)?;
+        connection
+            .execute_batch(
+                "CREATE TABLE IF NOT EXISTS tasks (
+                    id TEXT PRIMARY KEY,
+                    due TEXT,
+                    status TEXT NOT NULL,
+                    data BLOB NOT NULL
+                );
+                CREATE INDEX IF NOT EXISTS tasks_due ON tasks (due);
+                CREATE INDEX IF NOT EXISTS tasks_status ON tasks (status);",
+            )
+            .map_err(io::Error::other)?;
+        // Older and no longer configured statuses are renamed the same way as the decoded tasks,
+        // so that status queries find them
+        for name in ["OverDue", "Pending", "Completed"] {
+            if let Some(status) = TaskStatus::replacement(name) {
+                connection
+                    .execute(
+                        "UPDATE tasks SET status = ?1 WHERE status = ?2",
+                        [status.as_str(), name],
+                    )
+                    .map_err(io::Error::other)?;
+            }
+        }
+        Ok(Self {
+            dir,
+            encrypted,
+            connection,
+        })
+    }
+
+    fn file_path(dir: &Path, encrypted: bool) -> PathBuf {
+        if encrypted {
+            dir.join("tasks.enc.db")
+        } else {
+            dir.join("tasks.db")
+        }
+    }
+
+    fn encode(&self, task: &Task) -> io::Result<Vec<u8>> {
+        if self.encrypted {
+            Ok(crate::auth::encrypt_tasks(slice::from_ref(task)))
+        } else {
+            Ok(serde_json::to_vec(task)?)
+        }
+    }
+
+    fn decode(&self, data: &[u8]) -> io::Result<Task> {
+        if self.encrypted {
+            crate::auth::decrypt_tasks(data)?
+                .pop()
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Empty task row"))
+        } else {
+            Ok(serde_json::from_slice(data)?)
+        }
+    }
+
+    fn upsert(&self, task: &Task) -> io::Result<()> {
+        self.connection
+            .execute(
+                "INSERT INTO tasks (id, due, status, data) VALUES (?1, ?2, ?3, ?4)
+                ON CONFLICT (id) DO UPDATE SET
+                    due = excluded.due, status = excluded.status, data = excluded.data",
+                params![
+                    task.id.to_string(),
+                    task.due().map(|due| due.format(DUE_FORMAT).to_string()),
+                    task.status.as_str(),
+                    self.encode(task)?,
+                ],
+            )
+            .map_err(io::Error::other)?;
+        Ok(())
+    }
+
+    fn select(&self, condition: &str, values: &[&dyn ToSql]) -> io::Result<Vec<Task>> {
+        let sql = format!("SELECT data FROM tasks {} ORDER BY due", condition);
+        let mut statement = self.connection.prepare(&sql).map_err(io::Error::other)?;
+        let rows = statement
+            .query_map(values, |row| row.get::<_, Vec<u8>>("data"))
+            .map_err(io::Error::other)?;
+        let mut tasks = Vec::new();
+        for row in rows {
+            tasks.push(self.decode(&row.map_err(io::Error::other)?)?);
+        }
+        Ok(tasks)
+    }
+}
+
+impl Storage for SqliteStorage {
+    fn load(&self) -> io::Result<Vec<Task>> {
+        self.select("", &[])
+    }
+
+    fn save(&self, tasks: &[Task]) -> io::Result<()> {
+        let transaction = self
+            .connection
+            .unchecked_transaction()
+            .map_err(io::Error::other)?;
+        transaction
+            .execute("DELETE FROM tasks", [])
+            .map_err(io::Error::other)?;
+        for task in tasks {
+            self.upsert(task)?;
+        }
+        transaction.commit().map_err(io::Error::other)
+    }
+
+    fn save_task(&self, _tasks: &[Task], task: &Task) -> io::Result<()> {
+        self.upsert(task)
+    }
+
+    fn delete_task(&self, _tasks: &[Task], id: u128) -> io::Result<()> {
+        self.connection
+            .execute("DELETE FROM tasks WHERE id = ?1", [id.to_string()])
+            .map_err(io::Error::other)?;
+        Ok(())
+    }
+
+    fn query(&self, query: &Query) -> io::Result<Vec<Task>> {
+        let mut conditions = Vec::new();
+        let mut values: Vec<Box<dyn ToSql>> = Vec::new();
+
+        if !query.statuses.is_empty() {
+            let placeholders = vec!["?"; query.statuses.len()].join(", ");
+            conditions.push(format!("status IN ({})", placeholders));
+            for status in &query.statuses {
+                values.push(Box::new(status.as_str()));
+            }
+        }
+        if let Some(from) = query.due_from {
+            conditions.push("due >= ?".to_string());
+            values.push(Box::new(from.format(DUE_FORMAT).to_string()));
+        }
+        if let Some(to) = query.due_to {
+            conditions.push("due <= ?".to_string());
+            values.push(Box::new(to.format(DUE_FORMAT).to_string()));
+        }
+
+        let condition = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+        let values: Vec<&dyn ToSql> = values.iter().map(|value| value.as_ref()).collect();
+        self.select(&condition, &values)
+    }
+
+    fn path(&self) -> PathBuf {
+        Self::file_path(&self.dir, self.encrypted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Rows written by an older version, before the statuses were customized
+    fn insert_row(connection: &Connection, id: u128, status: &str) {
+        let data = serde_json::json!({
+            "id": id,
+            "title": status,
+            "date": "",
+            "time": "",
+            "description": "",
+            "status": status,
+        });
+        connection
+            .execute(
+                "INSERT INTO tasks (id, due, status, data) VALUES (?1, NULL, ?2, ?3)",
+                params![id.to_string(), status, serde_json::to_vec(&data).unwrap()],
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn renames_statuses_that_are_no_longer_configured() {
+        crate::tasks::set_test_statuses();
+        let dir = std::env::temp_dir().join(format!("todo-tui-{}", uuid::Uuid::now_v7()));
+        {
+            let storage = SqliteStorage::open(dir.clone(), false).unwrap();
+            insert_row(&storage.connection, 1, "Pending");
+            insert_row(&storage.connection, 2, "Completed");
+            insert_row(&storage.connection, 3, "Doing");
+        }
+
+        let storage = SqliteStorage::open(dir.clone(), false).unwrap();
+        let titles = |status: TaskStatus| -> Vec<String> {
+            let query = Query {
+                statuses: vec![status],
+                ..Default::default()
+            };
+            let tasks = storage.query(&query).unwrap();
+            tasks.into_iter().map(|task| task.title).collect()
+        };
+        assert_eq!(titles(TaskStatus::open()), ["Pending"]);
+        assert_eq!(titles(TaskStatus::done()), ["Completed"]);
+        assert_eq!(titles(TaskStatus::from("Doing".to_string())), ["Doing"]);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,262 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+    sync::mpsc::{self, Sender},
+    thread::{self, JoinHandle},
+};
+
+use crate::{
+    config::{Config, GitConfig},
+    storage::{Backend, JsonStorage, Storage},
+    tasks::Task,
+};
+
+const FALLBACK_NAME: &str = "TodoTUI";
+const FALLBACK_EMAIL: &str = "todotui@localhost";
+
+/// Returns the git settings if the active workspace is stored in a git repository.
+pub fn git_config(config: &Config) -> Option<&GitConfig> {
+    // Only the JSON files can be merged, a SQLite database is an opaque blob to git
+    config.git().filter(|_| config.backend() == Backend::Json)
+}
+
+/// Commits the task file, does nothing if it didn't change.
+pub fn commit(path: &Path, message: &str) -> io::Result<()> {
+    let (dir, file) = split_path(path)?;
+    init(dir)?;
+    if !path.exists() {
+        return Ok(());
+    }
+    run(dir, &["add", "--", file])?;
+    if !has_staged_changes(dir)? {
+        return Ok(());
+    }
+    commit_staged(dir, message)
+}
+
+/// Commits the task file on a background thread so that saving never waits for git.
+pub struct Committer {
+    sender: Option<Sender<(PathBuf, String)>>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl Committer {
+    pub fn new() -> Self {
+        let (sender, receiver) = mpsc::channel::<(PathBuf, String)>();
+        let worker = thread::spawn(move || {
+            while let Ok(first) = receiver.recv() {
+                // Changes saved while git was busy go into a single commit
+                let mut pending = vec![first];
+                pending.extend(receiver.try_iter());
+                while !pending.is_empty() {
+                    let path = pending[0].0.clone();
+                    let (same, rest) = pending.into_iter().partition(|(other, _)| *other == path);
+                    pending = rest;
+                    let messages: Vec<String> =
+                        same.into_iter().map(|(_, message)| message).collect();
+                    // History is a bonus, a failing commit must not lose the edit
+                    let _ = commit(&path, &combine(&messages));
+                }
+            }
+        });
+        Self {
+            sender: Some(sender),
+            worker: Some(worker),
+        }
+    }
+
+    /// Queues a commit of the task file at `path`.
+    pub fn commit(&self, path: PathBuf, message: String) {
+        if let Some(sender) = &self.sender {
+            let _ = sender.send((path, message));
+        }
+    }
+}
+
+impl Drop for Committer {
+    // Lets the queued commits finish before quitting
+    fn drop(&mut self) {
+        self.sender.take();
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+// `Add task "Rent"` or `3 changes` followed by the list of them
+fn combine(messages: &[String]) -> String {
+    match messages {
+        [message] => message.clone(),
+        messages => format!("{} changes\n\n{}", messages.len(), messages.join("\n")),
+    }
+}
+
+/// Pulls the remote task file, merges it task by task and pushes the result.
+pub fn sync(config: &Config) -> io::Result<String> {
+    let settings = git_config(config)
+        .ok_or_else(|| io::Error::other("Git sync is not enabled for the json backend"))?;
+    let remote = settings
+        .remote
+        .as_deref()
+        .ok_or_else(|| io::Error::other("No git remote configured"))?;
+    let storage = JsonStorage::new(config.data_dir(), config.encryption());
+    let path = storage.path();
+    let (dir, file) = split_path(&path)?;
+
+    commit(&path, "Save local changes before sync")?;
+    let has_head = git(dir, &["rev-parse", "--verify", "HEAD"])?
+        .status
+        .success();
+    let push = format!("HEAD:{}", settings.branch);
+
+    // Exit code 2 means the remote is reachable but the branch doesn't exist yet
+    let branch = format!("refs/heads/{}", settings.branch);
+    let remote_branch = git(dir, &["ls-remote", "--exit-code", remote, &branch])?;
+    match remote_branch.status.code() {
+        Some(0) => {}
+        Some(2) if has_head => {
+            run(dir, &["push", "--quiet", remote, &push])?;
+            return Ok("Pushed tasks to the remote.".to_string());
+        }
+        Some(2) => return Ok("Nothing to sync yet.".to_string()),
+        _ => {
+            let stderr = String::from_utf8_lossy(&remote_branch.stderr);
+            return Err(io::Error::other(format!(
+                "Failed to reach the remote: {}",
+                stderr.trim()
+            )));
+        }
+    }
+
+    run(dir, &["fetch", "--quiet", remote, &settings.branch])?;
+    let message = if !has_head || is_ancestor(dir, "HEAD", "FETCH_HEAD")? {
+        run(dir, &["merge", "--quiet", "--ff-only", "FETCH_HEAD"])?;
+        "Pulled tasks from the remote."
+    } else if is_ancestor(dir, "FETCH_HEAD", "HEAD")? {
+        run(dir, &["push", "--quiet", remote, &push])?;
+        "Pushed tasks to the remote."
+    } else {
+        merge(dir, file, &storage)?;
+        run(dir, &["push", "--quiet", remote, &push])?;
+        "Merged local and remote tasks."
+    };
+    Ok(message.to_string())
+}
+
+fn merge(dir: &Path, file: &str, storage: &JsonStorage) -> io::Result<()> {
+    let base = match run(dir, &["merge-base", "HEAD", "FETCH_HEAD"]) {
+        Ok(base) => Some(read_version(dir, base.trim(), file, storage)?),
+        // Unrelated histories, e.g. two machines that started syncing separately
+        Err(_) => None,
+    };
+    let local = read_version(dir, "HEAD", file, storage)?;
+    let remote = read_version(dir, "FETCH_HEAD", file, storage)?;
+    let (merged, _) = crate::merge::merge(base.as_deref(), &local, &remote);
+
+    // Record the merge without touching the working tree, the merged file is written below
+    run_with_identity(
+        dir,
+        &[
+            "merge",
+            "--no-commit",
+            "--allow-unrelated-histories",
+            "-s",
+            "ours",
+            "FETCH_HEAD",
+        ],
+    )?;
+    storage.save(&merged)?;
+    run(dir, &["add", "--", file])?;
+    commit_staged(dir, "Merge remote tasks")
+}
+
+fn read_version(
+    dir: &Path,
+    revision: &str,
+    file: &str,
+    storage: &JsonStorage,
+) -> io::Result<Vec<Task>> {
+    let output = git(dir, &["show", &format!("{}:{}", revision, file)])?;
+    if !output.status.success() {
+        // The file didn't exist in that revision
+        return Ok(Vec::new());
+    }
+    storage.decode(&output.stdout)
+}
+
+fn init(dir: &Path) -> io::Result<()> {
+    if !dir.join(".git").exists() {
+        std::fs::create_dir_all(dir)?;
+        run(dir, &["init", "--quiet"])?;
+        // Older versions kept the encryption key of a custom data dir next to the tasks
+        std::fs::write(
+            dir.join(".gitignore"),
+            format!("{}\n", crate::auth::KEY_FILE),
+        )?;
+    }
+    Ok(())
+}
+
+fn has_staged_changes(dir: &Path) -> io::Result<bool> {
+    Ok(!git(dir, &["diff", "--cached", "--quiet"])?.status.success())
+}
+
+fn commit_staged(dir: &Path, message: &str) -> io::Result<()> {
+    run_with_identity(dir, &["commit", "--quiet", "-m", message])?;
+    Ok(())
+}
+
+// Falls back to a generic identity so that commits work without a global git config
+fn run_with_identity(dir: &Path, args: &[&str]) -> io::Result<String> {
+    let name = run(dir, &["config", "user.name"]).unwrap_or_default();
+    let email = run(dir, &["config", "user.email"]).unwrap_or_default();
+    let name = format!("user.name={}", non_empty(&name, FALLBACK_NAME));
+    let email = format!("user.email={}", non_empty(&email, FALLBACK_EMAIL));
+    let mut full_args = vec!["-c", &name, "-c", &email];
+    full_args.extend_from_slice(args);
+    run(dir, &full_args)
+}
+
+fn non_empty<'a>(value: &'a str, fallback: &'a str) -> &'a str {
+    match value.trim() {
+        "" => fallback,
+        value => value,
+    }
+}
+
+fn is_ancestor(dir: &Path, ancestor: &str, revision: &str) -> io::Result<bool> {
+    Ok(
+        git(dir, &["merge-base", "--is-ancestor", ancestor, revision])?
+            .status
+            .success(),
+    )
+}
+
+fn split_path(path: &Path) -> io::Result<(&Path, &str)> {
+    match (
+        path.parent(),
+        path.file_name().and_then(|name| name.to_str()),
+    ) {
+        (Some(dir), Some(file)) => Ok((dir, file)),
+        _ => Err(io::Error::other("Invalid task file path")),
+    }
+}
+
+fn git(dir: &Path, args: &[&str]) -> io::Result<Output> {
+    Command::new("git").arg("-C").arg(dir).args(args).output()
+}
+
+// Runs git and turns a failing exit status into an error
+fn run(dir: &Path, args: &[&str]) -> io::Result<String> {
+    let output = git(dir, args)?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(io::Error::other(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            stderr.trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
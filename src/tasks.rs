@@ -1,24 +1,48 @@
-use chrono::NaiveDateTime;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
 use serde::{Deserialize, Serialize};
-use std::{fs, io, path::PathBuf};
+use std::{fs, io, path::PathBuf, sync::OnceLock};
+
+use crate::config::Status;
+
+static STATUSES: OnceLock<Vec<Status>> = OnceLock::new();
 
 #[derive(Serialize, Clone, PartialEq, Deserialize)]
 pub struct Task {
     pub id: u128,
     pub title: String,
+    /// `DD MM YYYY`, empty for tasks without a deadline
     pub date: String,
+    /// `HH MM`, empty for tasks due some time during the day
     pub time: String,
     pub description: String,
     pub status: TaskStatus,
+    /// Milliseconds since the epoch, zero for tasks created before this was recorded
+    #[serde(default)]
+    pub created_at: i64,
+    #[serde(default)]
+    pub updated_at: i64,
+    #[serde(default)]
+    pub completed_at: Option<i64>,
+    #[serde(default)]
+    pub modified: Modified,
 }
 
-#[derive(Serialize, Clone, PartialEq, Deserialize)]
-pub enum TaskStatus {
-    Completed,
-    Pending,
-    OverDue,
+/// When each field was last changed, in milliseconds since the epoch. Used to merge stores.
+#[derive(Serialize, Clone, PartialEq, Deserialize, Default)]
+#[serde(default)]
+pub struct Modified {
+    pub title: i64,
+    pub date: i64,
+    pub time: i64,
+    pub description: i64,
+    pub status: i64,
 }
 
+/// Name of one of the statuses configured in `Config`, see `statuses`.
+#[derive(Serialize, Clone, PartialEq, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub struct TaskStatus(String);
+
 impl Task {
     pub fn new() -> Self {
         Self {
@@ -27,7 +51,11 @@ impl Task {
             date: String::new(),
             time: String::new(),
             description: String::new(),
-            status: TaskStatus::Pending,
+            status: TaskStatus::open(),
+            created_at: 0,
+            updated_at: 0,
+            completed_at: None,
+            modified: Modified::default(),
         }
     }
 
@@ -38,67 +66,177 @@ impl Task {
             date: String::new(),
             time: String::new(),
             description: String::new(),
-            status: TaskStatus::Pending,
+            status: TaskStatus::open(),
+            created_at: 0,
+            updated_at: 0,
+            completed_at: None,
+            modified: Modified::default(),
+        }
+    }
+
+    /// Updates the timestamps for the fields that differ from the previous version.
+    pub fn touch(&mut self, previous: Option<&Task>) {
+        let now = chrono::Utc::now().timestamp_millis();
+        self.updated_at = now;
+        self.created_at = previous.map_or(now, |previous| previous.created_at);
+        self.completed_at = match previous {
+            _ if !self.status.is_done() => None,
+            Some(previous) if previous.status.is_done() => previous.completed_at,
+            _ => Some(now),
+        };
+
+        let Some(previous) = previous else {
+            self.modified = Modified {
+                title: now,
+                date: now,
+                time: now,
+                description: now,
+                status: now,
+            };
+            return;
+        };
+        self.modified = previous.modified.clone();
+        if self.title != previous.title {
+            self.modified.title = now;
+        }
+        if self.date != previous.date {
+            self.modified.date = now;
+        }
+        if self.time != previous.time {
+            self.modified.time = now;
+        }
+        if self.description != previous.description {
+            self.modified.description = now;
+        }
+        if self.status != previous.status {
+            self.modified.status = now;
         }
     }
 
+    /// The day the task is due, `None` for tasks without a date.
+    pub fn day(&self) -> Option<NaiveDate> {
+        NaiveDate::parse_from_str(&self.date, "%d %m %Y").ok()
+    }
+
+    /// The time the task is due, `None` when only a date is set.
+    pub fn time_of_day(&self) -> Option<NaiveTime> {
+        NaiveTime::parse_from_str(&self.time, "%H %M").ok()
+    }
+
+    /// When the task is due, the end of the day for tasks with only a date.
+    pub fn due(&self) -> Option<NaiveDateTime> {
+        let time = self
+            .time_of_day()
+            .unwrap_or(NaiveTime::from_hms_opt(23, 59, 59)?);
+        Some(self.day()?.and_time(time))
+    }
+
+    /// Sorts by due date with the tasks without a date last.
+    pub fn due_key(&self) -> (bool, Option<NaiveDateTime>) {
+        let due = self.due();
+        (due.is_none(), due)
+    }
+
+    /// Whether the task is still open after its due date.
     pub fn is_overdue(&self) -> bool {
         let now = chrono::Local::now().naive_local();
-        let date_time_str = format!("{} {}", self.time, self.date);
-        let date_time = NaiveDateTime::parse_from_str(&date_time_str, "%H %M %d %m %Y").unwrap();
-        self.status == TaskStatus::OverDue || self.status == TaskStatus::Pending && date_time < now
+        !self.status.is_done() && self.due().is_some_and(|due| due < now)
     }
 }
 
+impl TaskStatus {
+    /// The first open status, given to new and reopened tasks.
+    pub fn open() -> Self {
+        Self::first(|status| !status.done)
+    }
+
+    /// The first done status, given to completed tasks.
+    pub fn done() -> Self {
+        Self::first(|status| status.done)
+    }
 
-pub fn update_overdue(tasks: &mut [Task]) {
-    for task in tasks.iter_mut() {
-        if task.is_overdue() {
-            task.status = TaskStatus::OverDue;
+    // The configured statuses always contain an open and a done status
+    fn first(predicate: impl Fn(&Status) -> bool) -> Self {
+        let status = statuses().iter().find(|status| predicate(status)).unwrap();
+        Self(status.name.clone())
+    }
+
+    /// The configured status, `None` if it was removed from the config.
+    pub fn config(&self) -> Option<&'static Status> {
+        statuses().iter().find(|status| status.name == self.0)
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.config().is_some_and(|status| status.done)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The status that takes the place of a name from older versions, `None` for any other name.
+    pub fn replacement(name: &str) -> Option<Self> {
+        let configured = statuses().iter().any(|status| status.name == name);
+        match name {
+            // Overdue used to be stored as a status, it is derived from the due date now
+            "OverDue" => Some(Self::open()),
+            // The built-in statuses are replaced by user-defined ones, their tasks move along
+            "Pending" if !configured => Some(Self::open()),
+            "Completed" if !configured => Some(Self::done()),
+            _ => None,
         }
     }
 }
 
-fn get_data_dir() -> PathBuf {
-    crate::helpers::get_project_dir().data_dir().to_path_buf()
+impl From<String> for TaskStatus {
+    fn from(name: String) -> Self {
+        Self::replacement(&name).unwrap_or(Self(name))
+    }
 }
 
-pub fn reset() -> io::Result<()> {
-    let dir = get_data_dir();
-    let _ = fs::remove_file(dir.join("tasks.enc"));
-    Ok(())
+impl From<TaskStatus> for String {
+    fn from(status: TaskStatus) -> Self {
+        status.0
+    }
 }
 
-pub fn load() -> io::Result<Vec<Task>> {
-    let dir = get_data_dir();
-    let data = fs::read_to_string(dir.join("tasks.json"));
-    match data {
-        Ok(tasks) => Ok(serde_json::from_str(&tasks)?),
-        Err(_) => Ok(Vec::new()),
-    }
+/// Sets the statuses from the config, only the first call has an effect.
+pub fn set_statuses(statuses: Vec<Status>) {
+    let _ = STATUSES.set(statuses);
 }
 
-pub fn save(tasks: &[Task]) -> io::Result<()> {
-    let dir = get_data_dir();
-    fs::create_dir_all(&dir)?;
-    let path = dir.join("tasks.json");
-    let data = serde_json::to_string_pretty(tasks)?;
-    fs::write(path, data)
+/// The configured statuses in display order.
+pub fn statuses() -> &'static [Status] {
+    STATUSES.get_or_init(|| crate::config::Config::default().statuses())
 }
 
-pub fn load_encrypted() -> io::Result<Vec<Task>> {
-    let dir = get_data_dir();
-    let data = fs::read(dir.join("tasks.enc"));
-    match data {
-        Ok(tasks) => Ok(crate::auth::decrypt_tasks(&tasks)),
-        Err(_) => Ok(Vec::new()),
+/// Converts a timestamp in milliseconds since the epoch to local time.
+pub fn local_time(millis: i64) -> Option<NaiveDateTime> {
+    chrono::DateTime::from_timestamp_millis(millis)
+        .map(|time| time.with_timezone(&chrono::Local).naive_local())
+}
+
+pub fn get_data_dir() -> PathBuf {
+    match crate::helpers::data_dir_override() {
+        Some(dir) => dir.clone(),
+        None => crate::helpers::get_project_dir().data_dir().to_path_buf(),
     }
 }
 
-pub fn save_encrypted(tasks: &[Task]) -> io::Result<()> {
+pub fn reset() -> io::Result<()> {
     let dir = get_data_dir();
-    fs::create_dir_all(&dir)?;
-    let path = dir.join("tasks.enc");
-    let data = crate::auth::encrypt_tasks(&tasks);
-    fs::write(path, data)
+    let _ = fs::remove_file(dir.join("tasks.enc"));
+    let _ = fs::remove_file(dir.join("tasks.enc.db"));
+    Ok(())
+}
+
+/// Custom statuses for the tests, without the built-in Pending and Completed. The statuses are
+/// global, so every test that needs them uses this list.
+#[cfg(test)]
+pub fn set_test_statuses() {
+    set_statuses(vec![
+        Status::new("Todo", false),
+        Status::new("Doing", false),
+        Status::new("Done", true),
+    ]);
 }
@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+use std::{env, sync::OnceLock};
+
+use crate::config::TerminalConfig;
+
+// Detected once when the config is loaded, the config overrides the detection
+static CAPABILITIES: OnceLock<Capabilities> = OnceLock::new();
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum ColorSupport {
+    #[serde(rename = "16")]
+    Ansi16,
+    #[serde(rename = "256")]
+    Ansi256,
+    #[serde(rename = "truecolor")]
+    TrueColor,
+}
+
+pub struct Capabilities {
+    pub colors: ColorSupport,
+    pub nerd_font: bool,
+}
+
+pub fn init(config: &TerminalConfig) {
+    let _ = CAPABILITIES.set(Capabilities {
+        colors: config.colors.unwrap_or_else(detect_colors),
+        nerd_font: config.nerd_font.unwrap_or_else(detect_nerd_font),
+    });
+}
+
+pub fn capabilities() -> &'static Capabilities {
+    CAPABILITIES.get_or_init(|| Capabilities {
+        colors: detect_colors(),
+        nerd_font: detect_nerd_font(),
+    })
+}
+
+fn var(name: &str) -> String {
+    env::var(name).unwrap_or_default().to_lowercase()
+}
+
+fn detect_colors() -> ColorSupport {
+    let term = var("TERM");
+    let colorterm = var("COLORTERM");
+    if colorterm == "truecolor" || colorterm == "24bit" || term.ends_with("-direct") {
+        return ColorSupport::TrueColor;
+    }
+    // Terminals that support 24-bit colors without advertising it
+    if env::var_os("WT_SESSION").is_some()
+        || matches!(
+            var("TERM_PROGRAM").as_str(),
+            "iterm.app" | "wezterm" | "vscode"
+        )
+    {
+        return ColorSupport::TrueColor;
+    }
+    if term.contains("256color") {
+        return ColorSupport::Ansi256;
+    }
+    // The Windows console has no TERM but handles 24-bit colors since Windows 10
+    if cfg!(windows) && term.is_empty() {
+        return ColorSupport::TrueColor;
+    }
+    ColorSupport::Ansi16
+}
+
+// There is no way to ask the terminal for its font, so only the consoles that certainly lack
+// the glyphs are ruled out unless TODOTUI_NERD_FONT says otherwise
+fn detect_nerd_font() -> bool {
+    match var("TODOTUI_NERD_FONT").as_str() {
+        "1" | "true" | "yes" => return true,
+        "0" | "false" | "no" => return false,
+        _ => {}
+    }
+    let term = var("TERM");
+    !(term == "linux" || term == "dumb" || term.starts_with("vt"))
+}
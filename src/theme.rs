@@ -1,28 +1,290 @@
-#[derive(PartialEq)]
-pub enum Theme {
-    Default,
-    Compatible,
+use ratatui::style::Color;
+use serde::{Deserialize, Serialize};
+use std::{borrow::Cow, fs, sync::RwLock};
+
+use crate::terminal::ColorSupport;
+
+/// Colors and icons of the whole app.
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(deny_unknown_fields, default)]
+pub struct Theme {
+    /// Focused borders and popups
+    #[serde(with = "color")]
+    pub primary: Color,
+    /// Unfocused borders
+    #[serde(with = "color")]
+    pub secondary: Color,
+    /// Date headers of the task list, the calendar and the agenda
+    #[serde(with = "color")]
+    pub header: Color,
+    #[serde(with = "color")]
+    pub selection: Color,
+    #[serde(with = "color")]
+    pub overdue: Color,
+    /// Open tasks without a status color
+    #[serde(with = "color")]
+    pub pending: Color,
+    /// Completed tasks and other dimmed text
+    #[serde(with = "color")]
+    pub completed: Color,
+    /// Yes in prompts
+    #[serde(with = "color")]
+    pub success: Color,
+    /// No in prompts, invalid input and errors
+    #[serde(with = "color")]
+    pub error: Color,
+    pub icons: Icons,
 }
 
-impl Theme {
-    pub fn get_completed(&self) -> &str {
-        match self {
-            Theme::Default => "󰄴",
-            Theme::Compatible => "[x]",
-        }
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(deny_unknown_fields, default)]
+pub struct Icons {
+    pub completed: Cow<'static, str>,
+    pub uncompleted: Cow<'static, str>,
+}
+
+pub const NERD_ICONS: Icons = Icons {
+    completed: Cow::Borrowed("󰄴"),
+    uncompleted: Cow::Borrowed("󰄰"),
+};
+
+/// Used by the compatibility mode and by terminals without a Nerd Font.
+pub const ASCII_ICONS: Icons = Icons {
+    completed: Cow::Borrowed("[x]"),
+    uncompleted: Cow::Borrowed("[ ]"),
+};
+
+const DARK: Theme = Theme {
+    primary: Color::Rgb(166, 227, 161),
+    secondary: Color::Rgb(137, 180, 250),
+    header: Color::Rgb(137, 180, 250),
+    selection: Color::Rgb(249, 226, 175),
+    overdue: Color::Red,
+    pending: Color::Reset,
+    completed: Color::DarkGray,
+    success: Color::Rgb(0, 255, 0),
+    error: Color::Rgb(255, 0, 0),
+    icons: NERD_ICONS,
+};
+
+const LIGHT: Theme = Theme {
+    primary: Color::Rgb(64, 160, 43),
+    secondary: Color::Rgb(30, 102, 245),
+    header: Color::Rgb(30, 102, 245),
+    selection: Color::Rgb(254, 100, 11),
+    overdue: Color::Rgb(210, 15, 57),
+    pending: Color::Rgb(76, 79, 105),
+    completed: Color::Rgb(156, 160, 176),
+    success: Color::Rgb(64, 160, 43),
+    error: Color::Rgb(210, 15, 57),
+    icons: NERD_ICONS,
+};
+
+const HIGH_CONTRAST: Theme = Theme {
+    primary: Color::Rgb(255, 255, 0),
+    secondary: Color::Rgb(255, 255, 255),
+    header: Color::Rgb(0, 255, 255),
+    selection: Color::Rgb(255, 165, 0),
+    overdue: Color::Rgb(255, 80, 80),
+    pending: Color::Rgb(255, 255, 255),
+    completed: Color::Rgb(170, 170, 170),
+    success: Color::Rgb(0, 255, 0),
+    error: Color::Rgb(255, 80, 80),
+    icons: NERD_ICONS,
+};
+
+// Only the 16 ANSI colors, which the terminal palette decides on
+const ANSI: Theme = Theme {
+    primary: Color::Green,
+    secondary: Color::Blue,
+    header: Color::Cyan,
+    selection: Color::Yellow,
+    overdue: Color::Red,
+    pending: Color::Reset,
+    completed: Color::DarkGray,
+    success: Color::Green,
+    error: Color::Red,
+    icons: ASCII_ICONS,
+};
+
+// xterm's defaults, only used to find the closest one
+const ANSI_COLORS: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+// Steps of the 6x6x6 color cube of the 256 colors
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+const BUILT_IN: [(&str, Theme); 4] = [
+    ("dark", DARK),
+    ("light", LIGHT),
+    ("high-contrast", HIGH_CONTRAST),
+    ("16-color", ANSI),
+];
+
+static CURRENT: RwLock<Theme> = RwLock::new(DARK);
+
+impl Default for Theme {
+    fn default() -> Self {
+        DARK
+    }
+}
+
+impl Default for Icons {
+    fn default() -> Self {
+        NERD_ICONS
+    }
+}
+
+/// The active theme, widgets read it while rendering.
+pub fn current() -> Theme {
+    CURRENT.read().unwrap().clone()
+}
+
+/// Sets the active theme, fitted to the colors and the font of the terminal.
+pub fn set(mut theme: Theme) {
+    for color in [
+        &mut theme.primary,
+        &mut theme.secondary,
+        &mut theme.header,
+        &mut theme.selection,
+        &mut theme.overdue,
+        &mut theme.pending,
+        &mut theme.completed,
+        &mut theme.success,
+        &mut theme.error,
+    ] {
+        *color = fit(*color);
+    }
+    if !crate::terminal::capabilities().nerd_font && theme.icons == NERD_ICONS {
+        theme.icons = ASCII_ICONS;
     }
+    *CURRENT.write().unwrap() = theme;
+}
 
-    pub fn get_uncompleted(&self) -> &str {
-        match self {
-            Theme::Default => "󰄰",
-            Theme::Compatible => "[ ]",
+/// The closest color that the terminal can show.
+pub fn fit(color: Color) -> Color {
+    match (crate::terminal::capabilities().colors, color) {
+        (ColorSupport::TrueColor, _) => color,
+        (ColorSupport::Ansi256, Color::Rgb(r, g, b)) => Color::Indexed(to_256((r, g, b))),
+        (ColorSupport::Ansi16, Color::Indexed(index)) if index < 16 => {
+            ANSI_COLORS[index as usize].0
         }
+        (ColorSupport::Ansi16, _) => match to_rgb(color) {
+            Some(rgb) => ANSI_COLORS
+                .iter()
+                .min_by_key(|(_, ansi)| distance(*ansi, rgb))
+                .map_or(color, |(ansi, _)| *ansi),
+            None => color,
+        },
+        _ => color,
     }
+}
 
-    pub fn change_theme(&self) -> Self {
-        match self {
-            Theme::Default => Theme::Compatible,
-            Theme::Compatible => Theme::Default,
+// The 16 ANSI colors are left out, their value depends on the terminal
+fn to_rgb(color: Color) -> Option<(u8, u8, u8)> {
+    match color {
+        Color::Rgb(r, g, b) => Some((r, g, b)),
+        Color::Indexed(index @ 16..=231) => {
+            let index = (index - 16) as usize;
+            Some((
+                CUBE_LEVELS[index / 36],
+                CUBE_LEVELS[index / 6 % 6],
+                CUBE_LEVELS[index % 6],
+            ))
+        }
+        Color::Indexed(index @ 232..) => {
+            let gray = 8 + 10 * (index - 232);
+            Some((gray, gray, gray))
         }
+        _ => None,
+    }
+}
+
+// Either a color of the cube or a shade of the grayscale ramp, whichever is closer
+fn to_256(rgb: (u8, u8, u8)) -> u8 {
+    let level = |value: u8| {
+        (0..CUBE_LEVELS.len())
+            .min_by_key(|&i| CUBE_LEVELS[i].abs_diff(value))
+            .unwrap_or_default() as u8
+    };
+    let (r, g, b) = rgb;
+    let cube = 16 + 36 * level(r) + 6 * level(g) + level(b);
+
+    let average = (r as u16 + g as u16 + b as u16) / 3;
+    let gray = 232 + (average.saturating_sub(3) / 10).min(23) as u8;
+
+    [cube, gray]
+        .into_iter()
+        .min_by_key(|&index| to_rgb(Color::Indexed(index)).map_or(u32::MAX, |c| distance(c, rgb)))
+        .unwrap_or(cube)
+}
+
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let square = |x: u8, y: u8| (x.abs_diff(y) as u32).pow(2);
+    square(a.0, b.0) + square(a.1, b.1) + square(a.2, b.2)
+}
+
+/// Built-in themes followed by the files of the themes dir.
+pub fn names() -> Vec<String> {
+    let mut names: Vec<String> = BUILT_IN.iter().map(|(name, _)| name.to_string()).collect();
+    let mut files: Vec<String> = fs::read_dir(crate::config::Config::get_themes_dir())
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "toml")
+        })
+        .filter_map(|path| Some(path.file_stem()?.to_string_lossy().into_owned()))
+        .filter(|name| !names.contains(name))
+        .collect();
+    files.sort();
+    names.extend(files);
+    names
+}
+
+/// Loads `<name>.toml` from the themes dir or a built-in theme, so that a file can replace a
+/// built-in theme. Colors missing from a file are taken from the dark theme.
+pub fn load(name: &str) -> Option<Theme> {
+    let path = crate::config::Config::get_themes_dir().join(format!("{}.toml", name));
+    match fs::read_to_string(path) {
+        Ok(contents) => toml::from_str(&contents).ok(),
+        Err(_) => BUILT_IN
+            .iter()
+            .find(|(built_in, _)| *built_in == name)
+            .map(|(_, theme)| theme.clone()),
+    }
+}
+
+// Colors are written like `#a6e3a1`, `lightblue` or a 256 color index
+mod color {
+    use ratatui::style::Color;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(color: &Color, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(color)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        text.parse()
+            .map_err(|_| serde::de::Error::custom(format!("Unknown color `{}`", text)))
     }
 }
@@ -0,0 +1,145 @@
+use chrono::{Datelike, Days, NaiveDate, Timelike};
+use ratatui::{
+    prelude::*,
+    widgets::{Cell, Clear, Row, Table},
+};
+
+use crate::{tasks::Task, theme::Theme};
+
+use super::task_style;
+
+const HOURS: usize = 24;
+// Hour shown at the top before anything is selected
+const FIRST_HOUR: usize = 8;
+
+/// A week of tasks laid out in hourly rows, one column per day.
+pub struct Agenda {
+    start: NaiveDate,
+    offset: usize,
+    tasks: Vec<Task>,
+    selected: Option<u128>,
+}
+
+impl Widget for &mut Agenda {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        // Keep the selected task in view, the header takes one row
+        let visible = (area.height as usize).saturating_sub(1).min(HOURS);
+        if let Some(hour) = self.get_selected().map(|task| self.hour(task)) {
+            if hour < self.offset {
+                self.offset = hour;
+            } else if hour >= self.offset + visible {
+                self.offset = hour + 1 - visible;
+            }
+        }
+        self.offset = self.offset.min(HOURS - visible);
+
+        let theme = crate::theme::current();
+        let today = chrono::Local::now().date_naive();
+        let header_style = Style::new().fg(theme.header).bold();
+        let mut header = vec![Cell::from("")];
+        header.extend(self.days().map(|date| {
+            let style = if date == today {
+                header_style.underlined()
+            } else {
+                header_style
+            };
+            Cell::from(date.format("%a %d").to_string()).style(style)
+        }));
+
+        let rows = (self.offset..self.offset + visible).map(|hour| {
+            let mut cells = vec![Cell::from(format!("{:02}:00", hour)).fg(theme.completed)];
+            cells.extend(self.days().map(|date| self.render_slot(date, hour, &theme)));
+            Row::new(cells)
+        });
+
+        let mut widths = vec![Constraint::Length(5)];
+        widths.extend([Constraint::Ratio(1, 7); 7]);
+        let table = Table::new(rows, widths).header(Row::new(header));
+        Widget::render(table, area, buf);
+    }
+}
+
+impl Agenda {
+    pub fn new() -> Self {
+        Self {
+            start: Self::week_start(chrono::Local::now().date_naive()),
+            offset: FIRST_HOUR,
+            tasks: Vec::new(),
+            selected: None,
+        }
+    }
+
+    /// Monday of the week containing the date
+    pub fn week_start(date: NaiveDate) -> NaiveDate {
+        date - Days::new(date.weekday().num_days_from_monday() as u64)
+    }
+
+    pub fn start(&self) -> NaiveDate {
+        self.start
+    }
+
+    pub fn end(&self) -> NaiveDate {
+        self.start + Days::new(6)
+    }
+
+    pub fn show_week_of(&mut self, date: NaiveDate) {
+        self.start = Self::week_start(date);
+    }
+
+    pub fn previous_week(&mut self) {
+        self.start = self.start - Days::new(7);
+    }
+
+    pub fn next_week(&mut self) {
+        self.start = self.start + Days::new(7);
+    }
+
+    /// Sets the tasks of the displayed week and the one to highlight.
+    pub fn set_tasks(&mut self, tasks: Vec<Task>, selected: Option<u128>) {
+        self.tasks = tasks;
+        self.selected = selected;
+    }
+
+    fn days(&self) -> impl Iterator<Item = NaiveDate> {
+        self.start.iter_days().take(7)
+    }
+
+    fn get_selected(&self) -> Option<&Task> {
+        self.tasks
+            .iter()
+            .find(|task| Some(task.id) == self.selected)
+    }
+
+    fn hour(&self, task: &Task) -> usize {
+        // Tasks with only a date are shown at midnight
+        task.time_of_day()
+            .map(|time| time.hour() as usize)
+            .unwrap_or_default()
+    }
+
+    fn render_slot(&self, date: NaiveDate, hour: usize, theme: &Theme) -> Cell<'_> {
+        let date = date.format("%d %m %Y").to_string();
+        let mut tasks: Vec<&Task> = self
+            .tasks
+            .iter()
+            .filter(|task| task.date == date && self.hour(task) == hour)
+            .collect();
+        // The selected task is shown first so that it is always visible
+        tasks.sort_by_key(|task| (Some(task.id) != self.selected, task.time.clone()));
+        let Some(task) = tasks.first() else {
+            return Cell::from("");
+        };
+
+        let text = match tasks.len() {
+            1 => task.title.clone(),
+            count => format!("{} +{}", task.title, count - 1),
+        };
+        let mut style = task_style(task, theme);
+        if Some(task.id) == self.selected {
+            style = style.fg(theme.selection).reversed();
+        }
+        Cell::from(text).style(style)
+    }
+}
@@ -1,23 +1,40 @@
-use chrono::{NaiveDate, NaiveTime};
+use chrono::{Datelike, Days, NaiveDate};
 use ratatui::{
-    crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
+    crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind},
     prelude::*,
-    widgets::{Cell, Clear, List, ListState, Paragraph, Row, Table, TableState, Wrap},
+    widgets::{Block, Cell, Clear, List, ListState, Paragraph, Row, Table, TableState, Wrap},
 };
-use std::collections::BTreeMap;
-use tui_textarea::TextArea;
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+    fmt,
+    time::{Duration, Instant},
+};
+use tui_textarea::{CursorMove, TextArea};
 
 use crate::{
-    config::Config,
+    config::{Config, SavedSearch, ThemeConfig},
+    dates::QuickAdd,
     helpers::{PopupSize, rounded_block},
+    keybindings::Action,
+    search::{Search, SearchMatch},
+    state::UiState,
+    storage::Storage,
+    sync::Committer,
     tasks::{Task, TaskStatus},
-    theme::Theme,
+    theme::{ASCII_ICONS, Theme},
+    watcher::TaskWatcher,
 };
 
-use super::{Confirm, NewTask, OverDue};
+use super::{Agenda, Board, Calendar, CalendarAction, Confirm, NewTask, OverDue, Vim, VimAction};
 
 pub struct App<'a> {
-    theme: Theme,
+    /// Plain text checkboxes for terminals without a Nerd Font
+    ascii_icons: bool,
+    /// Picked in the theme picker
+    theme: Option<String>,
+    /// Width of the task list in percent
+    list_width: u16,
     tasks: Tasks,
     right_area: RightArea,
     new_task: NewTask<'a>,
@@ -28,15 +45,57 @@ pub struct App<'a> {
     total: usize,
     preview_scroll: (u16, u16),
     search: TextArea<'a>,
+    /// Why the search text couldn't be parsed
+    search_error: Option<String>,
+    /// Name of the search being saved
+    search_name: TextArea<'a>,
+    /// Task typed into the quick-add bar, like `Pay rent tomorrow 9am`
+    quick_add: TextArea<'a>,
     config: Config,
     over_due: OverDue,
     filter: Filter,
+    sort: SortMode,
+    /// Titles of the collapsed groups
+    collapsed: HashSet<String>,
+    workspaces: ListState,
+    themes: ThemePicker,
+    calendar: Calendar,
+    view: View,
+    agenda: Agenda,
+    board: Board,
+    vim: Vim<'a>,
+    /// Rows of the task list, for half page jumps
+    page_height: usize,
+    areas: Areas,
+    /// Time and table row of the last click, to detect double clicks
+    last_click: Option<(Instant, usize)>,
+    storage: Box<dyn Storage>,
+    watcher: Option<TaskWatcher>,
+    committer: Committer,
+}
+
+// Where everything was drawn, to find what was clicked
+#[derive(Default)]
+struct Areas {
+    main: Rect,
+    search: Rect,
+    list: Rect,
+    right: Rect,
+    /// Inside the borders of the task table
+    table: Rect,
+    /// Table row shown on each line of the table, `None` for the space after a date
+    table_lines: Vec<Option<usize>>,
 }
 
+// (selectable rows, tasks grouped for display, total row count)
+type GroupedTasks = (Vec<(usize, u128)>, Vec<Group>, usize);
+
 struct Tasks {
     list: Vec<Task>,
-    grouped: BTreeMap<NaiveDate, Vec<Task>>,
+    grouped: Vec<Group>,
     selectable: Vec<(usize, u128)>,
+    /// Fuzzy matches of the text search by task id, empty without one
+    matches: HashMap<u128, SearchMatch>,
 }
 
 #[derive(PartialEq)]
@@ -46,6 +105,14 @@ enum RightArea {
     EditTask,
 }
 
+/// How the task list is laid out
+#[derive(PartialEq)]
+enum View {
+    List,
+    Agenda,
+    Board,
+}
+
 #[derive(PartialEq)]
 enum AppFocus {
     LeftArea,
@@ -56,11 +123,83 @@ enum AppFocus {
     ToggleEnc,
     OverDue,
     Filter,
+    Workspace,
+    Calendar,
+    Command,
+    Theme,
+    SaveSearch,
+    QuickAdd,
 }
 
 struct Filter {
     state: ListState,
-    mode: Option<TaskStatus>,
+    mode: Option<StatusFilter>,
+    /// Set from the calendar
+    date: Option<NaiveDate>,
+}
+
+struct ThemePicker {
+    state: ListState,
+    names: Vec<String>,
+    /// Restored when the picker is cancelled
+    previous: Theme,
+}
+
+#[derive(Clone, PartialEq)]
+enum StatusFilter {
+    Status(TaskStatus),
+    Overdue,
+    Saved(SavedSearch),
+}
+
+/// Tasks under a header row of the task list
+struct Group {
+    /// `None` when the list isn't grouped, there is no header row then
+    title: Option<String>,
+    /// Only the header is shown, the board and the agenda still show the tasks
+    collapsed: bool,
+    tasks: Vec<Task>,
+}
+
+/// Groups of the bucketed mode, in display order
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Bucket {
+    Overdue,
+    Earlier,
+    Today,
+    Tomorrow,
+    ThisWeek,
+    Later,
+    Someday,
+}
+
+/// How the task list is grouped and ordered
+#[derive(Clone, Copy, PartialEq)]
+enum SortMode {
+    /// Grouped by due date, earliest first
+    DueAscending,
+    /// Grouped into Overdue, Today, Tomorrow, This Week, Later and Someday
+    Buckets,
+    DueDescending,
+    /// Grouped by status in the configured order, then by due date
+    Status,
+    // The rest is a single list without headers, timestamps sort the most recent first
+    Title,
+    Created,
+    Updated,
+    Completed,
+    /// By due date without date headers
+    None,
+}
+
+/// What changed in the task list, so that the storage only writes what is needed
+enum Change {
+    Added(u128),
+    Edited(u128),
+    StatusChanged(u128),
+    Deleted(Task),
+    /// Rewrites the whole store, with the reason for the history
+    All(&'static str),
 }
 
 enum ScrollDirection {
@@ -68,32 +207,114 @@ enum ScrollDirection {
     Down,
 }
 
-pub const PRIMARY_STYLE: Style = Style::new().fg(Color::Rgb(166, 227, 161));
-pub const SECONDARY_STYLE: Style = Style::new().fg(Color::Rgb(137, 180, 250));
-pub const GREEN_STYLE: Style = Style::new().fg(Color::Rgb(0, 255, 0));
-pub const RED_STYLE: Style = Style::new().fg(Color::Rgb(255, 0, 0));
-pub const SELECTION_STYLE: Style = Style::new().fg(Color::Rgb(249, 226, 175));
+// Everything the task list reacts to, the first matching action wins
+const LIST_ACTIONS: &[Action] = &[
+    Action::Quit,
+    Action::SwitchFocus,
+    Action::Up,
+    Action::Down,
+    Action::Left,
+    Action::Right,
+    Action::MoveLeft,
+    Action::MoveRight,
+    Action::Agenda,
+    Action::Board,
+    Action::Deselect,
+    Action::ToggleCompleted,
+    Action::ToggleIcons,
+    Action::NarrowList,
+    Action::WidenList,
+    Action::Delete,
+    Action::ToggleEncryption,
+    Action::Edit,
+    Action::Preview,
+    Action::Filter,
+    Action::Sort,
+    Action::Calendar,
+    Action::Workspaces,
+    Action::Themes,
+    Action::NewTask,
+    Action::QuickAdd,
+    Action::Search,
+    Action::SaveSearch,
+    Action::ToggleGroup,
+    Action::ToggleAllGroups,
+];
+
+const DOUBLE_CLICK: Duration = Duration::from_millis(500);
+
+const MIN_LIST_WIDTH: u16 = 20;
+const MAX_LIST_WIDTH: u16 = 80;
+
+const POPUP_ACTIONS: &[Action] = &[
+    Action::Confirm,
+    Action::Cancel,
+    Action::Quit,
+    Action::Down,
+    Action::Up,
+];
+
+/// Overdue tasks stand out, otherwise the color of the status is used and done tasks are dimmed.
+pub fn task_style(task: &Task, theme: &Theme) -> Style {
+    if task.is_overdue() {
+        return Style::new().fg(theme.overdue).bold();
+    }
+    let color = task
+        .status
+        .config()
+        .and_then(|status| status.color.as_deref())
+        .and_then(|color| color.parse::<Color>().ok())
+        .map(crate::theme::fit);
+    match (color, task.status.is_done()) {
+        (Some(color), true) => Style::default().fg(color),
+        (Some(color), false) => Style::default().fg(color).bold(),
+        (None, true) => Style::new().fg(theme.completed),
+        (None, false) => Style::new().fg(theme.pending).bold(),
+    }
+}
 
 impl App<'_> {
-    pub fn new(new: bool, config: Config) -> Self {
-        let mut tasks = if config.encryption {
-            crate::tasks::load_encrypted().unwrap_or_else(|_| Vec::new())
-        } else {
-            crate::tasks::load().unwrap_or_else(|_| Vec::new())
-        };
-
-        crate::tasks::update_overdue(&mut tasks);
-
-        let display_list = Self::get_display_tasks(&tasks, &None);
-        let group = Self::group_date_tasks(&display_list);
+    /// Fails when the tasks can't be read, starting with an empty list would overwrite them.
+    pub fn new(new: bool, config: Config) -> Result<Self, String> {
+        let storage = crate::storage::open(&config)
+            .map_err(|error| format!("Failed to open the task storage: {}", error))?;
+        let tasks = Self::load_tasks(storage.as_ref()).map_err(|error| {
+            format!(
+                "Failed to load the tasks from {}: {}",
+                storage.path().display(),
+                error
+            )
+        })?;
+
+        let ui_state = UiState::load(&config.data_dir());
+        if let Some(theme) = ui_state.theme.as_deref().and_then(crate::theme::load) {
+            crate::theme::set(theme);
+        }
+        // Statuses may have changed since, the filter is looked up by name
+        let mut filter_options = Self::get_filter_options(&config);
+        let filter_index = ui_state
+            .filter
+            .as_ref()
+            .and_then(|name| filter_options.iter().position(|(option, _)| option == name))
+            .unwrap_or(0);
+        let filter_mode = filter_options.swap_remove(filter_index).1;
+
+        let display_list = Self::get_display_tasks(&tasks, &filter_mode, None).unwrap_or_default();
+        let group = Self::group_tasks(
+            &display_list,
+            SortMode::DueAscending,
+            &HashMap::new(),
+            &HashSet::new(),
+        );
 
-        let mut text_area = TextArea::default();
+        let mut text_area = TextArea::new(vec![ui_state.search]);
+        text_area.move_cursor(CursorMove::End);
         text_area.set_placeholder_text(
-            "Press / to search by title or date range (DD MM YYYY - DD MM YYYY)",
+            "Press / to search, e.g. status:pending due:<friday \"exact phrase\" -excluded",
         );
         text_area.set_cursor_line_style(Style::default());
 
-        let overdue_tasks = OverDue::get_tasks(&tasks);
+        let overdue_tasks = OverDue::get_tasks(storage.as_ref());
 
         let focus = if new {
             AppFocus::FirstTimeSetup
@@ -105,43 +326,159 @@ impl App<'_> {
             AppFocus::LeftArea
         };
 
-        Self {
+        let mut app = Self {
             focus,
             state_save: None,
             state: TableState::default(),
             total: group.2,
             preview_scroll: (0, 0),
-            theme: Theme::Default,
+            ascii_icons: ui_state.ascii_icons,
+            theme: ui_state.theme,
+            list_width: ui_state.list_width.clamp(MIN_LIST_WIDTH, MAX_LIST_WIDTH),
             over_due: OverDue::new(overdue_tasks),
             tasks: Tasks {
                 list: tasks,
                 grouped: group.1,
                 selectable: group.0,
+                matches: HashMap::new(),
             },
             new_task: NewTask::new(),
             new_task_save: None,
             right_area: RightArea::NewTask,
             search: text_area,
+            search_error: None,
+            search_name: TextArea::default(),
+            quick_add: TextArea::default(),
             filter: Filter {
-                state: ListState::default().with_selected(Some(0)),
-                mode: None,
+                state: ListState::default().with_selected(Some(filter_index)),
+                mode: filter_mode,
+                date: None,
+            },
+            sort: SortMode::DueAscending,
+            collapsed: HashSet::new(),
+            workspaces: ListState::default(),
+            themes: ThemePicker {
+                state: ListState::default(),
+                names: Vec::new(),
+                previous: Theme::default(),
             },
+            calendar: Calendar::new(),
+            view: View::List,
+            agenda: Agenda::new(),
+            board: Board::new(),
+            vim: Vim::new(),
+            page_height: 0,
+            areas: Areas::default(),
+            last_click: None,
+            // Live reload is best effort, the app works fine without it
+            watcher: TaskWatcher::new(&storage.path()).ok(),
+            committer: Committer::new(),
+            storage,
             config,
+        };
+        if !app.search.lines()[0].is_empty() {
+            app.apply_search();
         }
+        app.select_by_id(ui_state.selected);
+        Ok(app)
     }
 
-    fn get_display_tasks(tasks: &[Task], display_status: &Option<TaskStatus>) -> Vec<Task> {
-        match display_status {
-            Some(status) => tasks
-                .iter()
-                .filter(|t| t.status == *status)
-                .cloned()
-                .collect(),
-            None => tasks.to_vec(),
+    /// Remembers the theme, filter, search, selection and layout for the next start.
+    pub fn save_state(&self) {
+        let filter = Self::get_filter_options(&self.config)
+            .into_iter()
+            .find(|(_, mode)| mode.is_some() && *mode == self.filter.mode)
+            .map(|(name, _)| name);
+        let state = UiState {
+            theme: self.theme.clone(),
+            ascii_icons: self.ascii_icons,
+            filter,
+            search: self.search.lines()[0].clone(),
+            selected: self.get_selected().map(|task| task.id),
+            list_width: self.list_width,
+        };
+        state.save(&self.config.data_dir());
+    }
+
+    fn load_tasks(storage: &dyn Storage) -> std::io::Result<Vec<Task>> {
+        storage.load()
+    }
+
+    // Needed whenever the config changes where the tasks are stored, on failure the config goes
+    // back to `previous` and the current storage stays open
+    fn open_storage(&mut self, previous: Config) -> bool {
+        match crate::storage::open(&self.config) {
+            Ok(storage) => {
+                self.storage = storage;
+                self.watcher = TaskWatcher::new(&self.storage.path()).ok();
+                true
+            }
+            Err(error) => {
+                self.config = previous;
+                self.vim.message = Some(format!("Failed to open the task storage: {}", error));
+                false
+            }
+        }
+    }
+
+    /// Reloads the task list if the task file was changed by another program.
+    pub fn reload_if_changed(&mut self) {
+        if !self.watcher.as_ref().is_some_and(TaskWatcher::changed) {
+            return;
+        }
+        // The file may be half written, the next event will reload it
+        let Ok(tasks) = Self::load_tasks(self.storage.as_ref()) else {
+            return;
+        };
+        // Our own saves trigger events too, the list is kept in display order
+        fn by_id(tasks: &[Task]) -> Vec<&Task> {
+            let mut tasks: Vec<&Task> = tasks.iter().collect();
+            tasks.sort_by_key(|task| task.id);
+            tasks
         }
+        if by_id(&tasks) == by_id(&self.tasks.list) {
+            return;
+        }
+
+        let selected = self.get_selected().map(|task| task.id);
+        self.tasks.list = tasks;
+        self.refresh_task_list();
+        self.select_by_id(selected);
+    }
+
+    /// Fails when the query of a saved search is invalid.
+    fn get_display_tasks(
+        tasks: &[Task],
+        display_status: &Option<StatusFilter>,
+        date: Option<NaiveDate>,
+    ) -> Result<Vec<Task>, String> {
+        let search = match display_status {
+            Some(StatusFilter::Saved(saved)) => {
+                let today = chrono::Local::now().date_naive();
+                let search = Search::parse(&saved.query, today)
+                    .map_err(|error| format!("{}: {}", saved.name, error))?;
+                Some(search)
+            }
+            _ => None,
+        };
+        let date = date.map(|date| date.format("%d %m %Y").to_string());
+        Ok(tasks
+            .iter()
+            .filter(|t| match display_status {
+                Some(StatusFilter::Status(status)) => t.status == *status,
+                Some(StatusFilter::Overdue) => t.is_overdue(),
+                Some(StatusFilter::Saved(_)) => search
+                    .as_ref()
+                    .is_some_and(|search| search.matches(t).is_some()),
+                None => true,
+            })
+            .filter(|t| date.as_ref().is_none_or(|date| t.date == *date))
+            .cloned()
+            .collect())
     }
 
     pub fn draw(&mut self, frame: &mut Frame, area: Rect) {
+        let theme = crate::theme::current();
         frame.render_widget(Clear, area);
         // The right area determination should always be first so that the footer text get correctly
         if self.state.selected().is_none() {
@@ -150,89 +487,91 @@ impl App<'_> {
             self.right_area = RightArea::Preview
         }
 
-        let footer_text: Line = self.get_footer_text().into();
+        let footer_text: Line = match &self.vim.message {
+            Some(message) => Line::styled(message.clone(), theme.error),
+            None => self.get_footer_text().into(),
+        };
         let footer_height =
             (1 + footer_text.width().try_into().unwrap_or(0) / (area.width + 1)).min(3);
 
         let [main_area, footer_area] =
             Layout::vertical([Constraint::Fill(1), Constraint::Length(footer_height)]).areas(area);
 
-        let [left_area, right_area] =
-            Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
-                .areas(main_area);
+        let [left_area, right_area] = Layout::horizontal([
+            Constraint::Percentage(self.list_width),
+            Constraint::Percentage(100 - self.list_width),
+        ])
+        .areas(main_area);
 
         // Left area
         let [search_area, list_area] =
             Layout::vertical([Constraint::Length(3), Constraint::Min(1)]).areas(left_area);
+        self.areas.main = main_area;
+        self.areas.search = search_area;
+        self.areas.list = list_area;
+        self.areas.right = right_area;
 
         // Render Search
         let border_style = self.get_border_style(AppFocus::Search);
-        let cursor_style = if border_style == PRIMARY_STYLE {
+        let cursor_style = if self.focus == AppFocus::Search {
             Style::default().reversed()
         } else {
             Style::default()
         };
         self.search.set_cursor_style(cursor_style);
-        self.search
-            .set_block(rounded_block(" Search ".into(), border_style));
+        let mut search_block = rounded_block(" Search ".into(), border_style);
+        if let Some(error) = &self.search_error {
+            search_block =
+                search_block.title_bottom(Line::styled(format!(" {} ", error), theme.error));
+        }
+        self.search.set_block(search_block);
         frame.render_widget(&self.search, search_area);
 
         // Render Task list
+        self.page_height = list_area.height.saturating_sub(2).into();
         let style = self.get_border_style(AppFocus::LeftArea);
-        let title = match self.filter.mode {
-            Some(TaskStatus::Completed) => " Tasks - Completed ",
-            Some(TaskStatus::OverDue) => " Tasks - Overdue ",
-            Some(TaskStatus::Pending) => " Tasks - Pending ",
-            None => " Tasks ",
+        let mut title = match self.view {
+            View::List => " Tasks".to_string(),
+            View::Board => " Board".to_string(),
+            View::Agenda => format!(
+                " Agenda {} - {}",
+                self.agenda.start().format("%b %d"),
+                self.agenda.end().format("%b %d %Y")
+            ),
         };
-        let block = crate::helpers::rounded_block(title.into(), style);
-
-        let mut rows: Vec<Row> = Vec::with_capacity(self.total);
-        let now = chrono::Local::now().naive_local();
-
-        for (date, tasks) in &self.tasks.grouped {
-            // Format the date header based on its relation to today
-            let date_header = if *date == now.date() {
-                "Today".to_string()
-            } else if *date == now.date().succ_opt().unwrap_or(now.date()) {
-                "Tomorrow".to_string()
-            } else {
-                format!("{} {}", date.format("%a"), date.format("%b %d %Y"))
-            };
-
-            // Add a date header row (Non-selectable)
-            let header_row = Row::new(vec![Cell::from(date_header).style(SECONDARY_STYLE.bold())]);
-
-            rows.push(header_row);
-            // Add tasks under the date
-            for (i, task) in tasks.iter().enumerate() {
-                let title = task.title.as_str();
-
-                let (icon, style) = if task.status == TaskStatus::Completed {
-                    (self.theme.get_completed(), Style::default().dark_gray())
-                } else if task.is_overdue() {
-                    (self.theme.get_uncompleted(), Style::default().red().bold())
-                } else {
-                    (self.theme.get_uncompleted(), Style::default().bold())
-                };
-
-                let mut task_row =
-                    Row::new(vec![Cell::from(format!("{} {}", icon, title)).style(style)]);
-
-                // Last task of the date add a extra line to separate the next date
-                if i == tasks.len() - 1 {
-                    task_row = task_row.bottom_margin(1);
-                }
-
-                rows.push(task_row);
+        match &self.filter.mode {
+            Some(StatusFilter::Status(status)) => {
+                title.push_str(&format!(" - {}", status.as_str()))
             }
+            Some(StatusFilter::Overdue) => title.push_str(" - Overdue"),
+            Some(StatusFilter::Saved(saved)) => title.push_str(&format!(" - {}", saved.name)),
+            None => {}
+        }
+        if self.sort != SortMode::DueAscending {
+            title.push_str(&format!(" - {}", self.sort.label()));
+        }
+        if let Some(date) = self.filter.date {
+            title.push_str(&format!(" - {}", date.format("%a %b %d %Y")));
+        }
+        title.push(' ');
+        let workspace = format!(" {} ", self.config.workspace_name());
+        let block = crate::helpers::rounded_block(title.into(), style)
+            .title(Line::from(workspace).right_aligned());
+
+        if self.view == View::Agenda {
+            frame.render_widget(&block, list_area);
+
+            let week = self.week_tasks();
+            let selected = self.get_selected().map(|task| task.id);
+            self.agenda.set_tasks(week, selected);
+            frame.render_widget(&mut self.agenda, block.inner(list_area));
+        } else if self.view == View::Board {
+            frame.render_widget(&block, list_area);
+            self.update_board();
+            frame.render_widget(&mut self.board, block.inner(list_area));
+        } else {
+            self.render_task_table(frame, block, list_area);
         }
-
-        let table = Table::new(rows, &[Constraint::Fill(1)])
-            .block(block)
-            .row_highlight_style(SELECTION_STYLE);
-
-        frame.render_stateful_widget(table, list_area, &mut self.state);
 
         // Right area
         // Render Border
@@ -251,14 +590,26 @@ impl App<'_> {
         let right_area = block.inner(right_area);
         if self.right_area == RightArea::Preview {
             let task = self.get_selected().unwrap();
+            let timestamps = Self::get_timestamp_lines(&task);
+            let [timestamps_area, description_area] = Layout::vertical([
+                Constraint::Length(timestamps.len() as u16 + 1),
+                Constraint::Fill(1),
+            ])
+            .areas(right_area);
+            frame.render_widget(
+                Paragraph::new(timestamps).style(theme.completed),
+                timestamps_area,
+            );
+
             let description = task.description.as_str();
             self.verify_preview_scroll(description.lines().count() as u16, area);
             let text = tui_markdown::from_str(description).style(Style::default());
             let preview = Paragraph::new(text)
                 .scroll(self.preview_scroll)
                 .wrap(Wrap { trim: true });
-            frame.render_widget(preview, right_area);
+            frame.render_widget(preview, description_area);
         } else {
+            self.new_task.set_tasks(&self.tasks.list);
             frame.render_widget(&mut self.new_task, right_area);
         }
 
@@ -269,19 +620,148 @@ impl App<'_> {
 
         frame.render_widget(footer, footer_area);
 
+        if self.focus == AppFocus::Command {
+            let [prompt_area, command_area] =
+                Layout::horizontal([Constraint::Length(1), Constraint::Fill(1)]).areas(footer_area);
+            frame.render_widget(":", prompt_area);
+            frame.render_widget(&self.vim.command_line, command_area);
+        } else if self.focus == AppFocus::SaveSearch {
+            let prompt = "Save search as: ";
+            let [prompt_area, name_area] =
+                Layout::horizontal([Constraint::Length(prompt.len() as u16), Constraint::Fill(1)])
+                    .areas(footer_area);
+            frame.render_widget(prompt, prompt_area);
+            frame.render_widget(&self.search_name, name_area);
+        } else if self.focus == AppFocus::QuickAdd {
+            // What the task will be saved as is shown while typing
+            let today = chrono::Local::now().date_naive();
+            let parsed = QuickAdd::parse(&self.quick_add.lines()[0], today);
+            let preview = match parsed.title.as_str() {
+                "" => Span::styled(" Type a title ", theme.error),
+                title => Span::styled(
+                    format!(" \"{}\" {} ", title, parsed.due.describe()),
+                    theme.success,
+                ),
+            };
+            let prompt = "Add: ";
+            let [prompt_area, input_area, preview_area] = Layout::horizontal([
+                Constraint::Length(prompt.len() as u16),
+                Constraint::Fill(1),
+                Constraint::Length(preview.width() as u16),
+            ])
+            .areas(footer_area);
+            frame.render_widget(prompt, prompt_area);
+            frame.render_widget(&self.quick_add, input_area);
+            frame.render_widget(preview, preview_area);
+        }
+
         // Render popup prompts
         match self.focus {
             AppFocus::OverDue => frame.render_widget(&mut self.over_due, main_area),
-            AppFocus::DeletePrompt => {
-                let confirm = Confirm::new(
-                    " Delete Task ".into(),
-                    "Delete the selected task?".into(),
-                    PopupSize::Percentage { x: 20, y: 15 },
+            AppFocus::Calendar => {
+                self.calendar.set_tasks(&self.tasks.list);
+                frame.render_widget(&mut self.calendar, list_area);
+            }
+            AppFocus::DeletePrompt | AppFocus::ToggleEnc | AppFocus::FirstTimeSetup => {
+                if let Some(confirm) = self.confirm_prompt() {
+                    frame.render_widget(confirm, main_area);
+                }
+            }
+            AppFocus::Filter => {
+                let options = Self::get_filter_options(&self.config);
+                let width = options
+                    .iter()
+                    .map(|(name, _)| name.len())
+                    .max()
+                    .unwrap_or(0)
+                    + 8;
+                let popup_area = crate::helpers::create_popup_area(
+                    main_area,
+                    &PopupSize::Fixed {
+                        width: width.max(17) as u16,
+                        height: options.len() as u16 + 2,
+                    },
+                );
+                let block = crate::helpers::rounded_block(
+                    Line::from(" Filter ").centered(),
+                    theme.primary.into(),
+                );
+                // The first nine can be picked with their number
+                let list: Vec<Line> = options
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, (name, _))| match i {
+                        0..9 => Line::from(format!("{} {}", i + 1, name)).centered(),
+                        _ => Line::from(name).centered(),
+                    })
+                    .collect();
+                let list = List::new(list)
+                    .block(block)
+                    .highlight_style(theme.selection);
+                frame.render_widget(Clear, popup_area);
+                frame.render_stateful_widget(list, popup_area, &mut self.filter.state);
+            }
+            AppFocus::Workspace => {
+                let names = self.config.workspace_names();
+                let width = names.iter().map(|name| name.len()).max().unwrap_or(0) + 6;
+                let popup_area = crate::helpers::create_popup_area(
+                    main_area,
+                    &PopupSize::Fixed {
+                        width: width.max(16) as u16,
+                        height: names.len() as u16 + 2,
+                    },
+                );
+                let block = crate::helpers::rounded_block(
+                    Line::from(" Workspaces ").centered(),
+                    theme.primary.into(),
                 );
-                frame.render_widget(confirm, main_area);
+                let list: Vec<Line> = names
+                    .into_iter()
+                    .map(|name| Line::from(name).centered())
+                    .collect();
+                let list = List::new(list)
+                    .block(block)
+                    .highlight_style(theme.selection);
+                frame.render_widget(Clear, popup_area);
+                frame.render_stateful_widget(list, popup_area, &mut self.workspaces);
             }
+            AppFocus::Theme => {
+                let names = &self.themes.names;
+                let width = names.iter().map(|name| name.len()).max().unwrap_or(0) + 6;
+                let popup_area = crate::helpers::create_popup_area(
+                    main_area,
+                    &PopupSize::Fixed {
+                        width: width.max(12) as u16,
+                        height: names.len() as u16 + 2,
+                    },
+                );
+                let block = crate::helpers::rounded_block(
+                    Line::from(" Themes ").centered(),
+                    theme.primary.into(),
+                );
+                let list: Vec<Line> = names
+                    .iter()
+                    .map(|name| Line::from(name.as_str()).centered())
+                    .collect();
+                let list = List::new(list)
+                    .block(block)
+                    .highlight_style(theme.selection);
+                frame.render_widget(Clear, popup_area);
+                frame.render_stateful_widget(list, popup_area, &mut self.themes.state);
+            }
+            _ => {}
+        }
+    }
+
+    fn confirm_prompt(&self) -> Option<Confirm> {
+        let confirm = match self.focus {
+            AppFocus::DeletePrompt => Confirm::new(
+                " Delete Task ".into(),
+                "Delete the selected task?".into(),
+                PopupSize::Percentage { x: 20, y: 15 },
+            ),
             AppFocus::ToggleEnc => {
-                let (title, body) = if self.config.encryption {
+                let (title, body) = if self.config.encryption() {
                     (
                         " Disable Encryption ",
                         "Would you like to disable encryption?",
@@ -292,113 +772,303 @@ impl App<'_> {
                         "Would you like to enable encryption?",
                     )
                 };
-                let confirm = Confirm::new(
+                Confirm::new(
                     title.into(),
                     body.into(),
                     PopupSize::Percentage { x: 20, y: 15 },
-                );
-                frame.render_widget(confirm, main_area);
+                )
             }
-            AppFocus::FirstTimeSetup => {
-                let widget = Confirm::new(
-                    " Welcome to TodoTUI ".into(),
-                    "This is a one time setup. \n Would you like to enable encryption?".into(),
-                    PopupSize::Percentage { x: 20, y: 20 },
-                );
-                frame.render_widget(widget, main_area);
+            AppFocus::FirstTimeSetup => Confirm::new(
+                " Welcome to TodoTUI ".into(),
+                "This is a one time setup. \n Would you like to enable encryption?".into(),
+                PopupSize::Percentage { x: 20, y: 20 },
+            ),
+            _ => return None,
+        };
+        Some(confirm)
+    }
+
+    fn render_task_table(&mut self, frame: &mut Frame, block: Block, list_area: Rect) {
+        let theme = crate::theme::current();
+        let icons = if self.ascii_icons {
+            &ASCII_ICONS
+        } else {
+            &theme.icons
+        };
+        let mut rows: Vec<Row> = Vec::with_capacity(self.total);
+        // Lines taken by each row, to map clicks back to rows
+        let mut heights: Vec<u16> = Vec::with_capacity(self.total);
+
+        for group in &self.tasks.grouped {
+            // Add a header row (Non-selectable)
+            if let Some(header) = &group.title {
+                let header_style = Style::new().fg(theme.header).bold();
+                let header_row = if group.collapsed {
+                    // Collapsed groups show how many tasks they hide
+                    let text = format!("{} [+{}]", header, group.tasks.len());
+                    Row::new(vec![Cell::from(text).style(header_style)]).bottom_margin(1)
+                } else {
+                    Row::new(vec![Cell::from(header.as_str()).style(header_style)])
+                };
+                rows.push(header_row);
+                heights.push(1 + group.collapsed as u16);
             }
-            AppFocus::Filter => {
-                let popup_area = crate::helpers::create_popup_area(
-                    main_area,
-                    &PopupSize::Fixed {
-                        width: 17,
-                        height: 6,
-                    },
-                );
-                let block =
-                    crate::helpers::rounded_block(Line::from(" Filter ").centered(), PRIMARY_STYLE);
-                let list = vec![
-                    Line::from("All").centered(),
-                    Line::from("Completed").centered(),
-                    Line::from("Pending").centered(),
-                    Line::from("Overdue").centered(),
-                ];
-                let list = List::new(list)
-                    .block(block)
-                    .highlight_style(SELECTION_STYLE);
-                frame.render_widget(Clear, popup_area);
-                frame.render_stateful_widget(list, popup_area, &mut self.filter.state);
+            // Add tasks under the header
+            if group.collapsed {
+                continue;
+            }
+            let tasks = &group.tasks;
+            for (i, task) in tasks.iter().enumerate() {
+                let title = task.title.as_str();
+
+                let icon = match task.status.config().and_then(|s| s.icon.as_deref()) {
+                    Some(icon) => icon,
+                    None if task.status.is_done() => &icons.completed,
+                    None => &icons.uncompleted,
+                };
+                let style = task_style(task, &theme);
+
+                let mut line = Line::from(format!("{} ", icon));
+                match self.tasks.matches.get(&task.id) {
+                    Some(found) if !found.title.is_empty() => {
+                        let matched = Style::new().bold().underlined();
+                        line.extend(title.chars().enumerate().map(|(i, c)| {
+                            if found.title.contains(&i) {
+                                Span::styled(c.to_string(), matched)
+                            } else {
+                                Span::raw(c.to_string())
+                            }
+                        }));
+                    }
+                    _ => line.push_span(title),
+                }
+                let mut task_row = Row::new(vec![Cell::from(line).style(style)]);
+
+                // Last task of the group add a extra line to separate the next group
+                let last = i == tasks.len() - 1 && group.title.is_some();
+                if last {
+                    task_row = task_row.bottom_margin(1);
+                }
+
+                rows.push(task_row);
+                heights.push(1 + last as u16);
             }
-            _ => {}
         }
+
+        let inner = block.inner(list_area);
+        let table = Table::new(rows, &[Constraint::Fill(1)])
+            .block(block)
+            .row_highlight_style(theme.selection);
+
+        frame.render_stateful_widget(table, list_area, &mut self.state);
+
+        self.areas.table = inner;
+        self.areas.table_lines = heights
+            .iter()
+            .enumerate()
+            .skip(self.state.offset())
+            .flat_map(|(row, height)| std::iter::once(Some(row)).chain((1..*height).map(|_| None)))
+            .take(inner.height as usize)
+            .collect();
+    }
+
+    fn get_timestamp_lines(task: &Task) -> Vec<Line<'static>> {
+        let timestamps = [
+            ("Created", Some(task.created_at)),
+            ("Updated", Some(task.updated_at)),
+            ("Completed", task.completed_at),
+        ];
+        timestamps
+            .into_iter()
+            .filter_map(|(label, millis)| {
+                // Zero means the task is older than the timestamps
+                let time = millis.filter(|millis| *millis != 0)?;
+                let time = crate::tasks::local_time(time)?;
+                Some(Line::from(format!(
+                    "{:<10} {}",
+                    label,
+                    time.format("%a %b %d %Y %H:%M")
+                )))
+            })
+            .collect()
     }
 
-    fn get_filtered_tasks(&self) -> Vec<Task> {
+    /// Tasks found by the search, with how well they matched.
+    fn get_filtered_tasks(&self) -> Result<(Vec<Task>, HashMap<u128, SearchMatch>), String> {
         let search_text = &self.search.lines()[0];
-        if search_text.is_empty() {
-            return self.tasks.list.clone();
+        if search_text.trim().is_empty() {
+            return Ok((self.tasks.list.clone(), HashMap::new()));
         }
 
-        let dates: Vec<&str> = search_text.split(" - ").collect();
-        if dates.len() == 2 {
-            let date1 = NaiveDate::parse_from_str(dates[0].trim(), "%d %m %Y").ok();
-            let date2 = NaiveDate::parse_from_str(dates[1].trim(), "%d %m %Y").ok();
-
-            if let (Some(date1), Some(date2)) = (date1, date2) {
-                return self
-                    .tasks
-                    .list
-                    .iter()
-                    .filter(|t| {
-                        let task_date = NaiveDate::parse_from_str(&t.date, "%d %m %Y").unwrap();
-                        task_date >= date1 && task_date <= date2
-                    })
-                    .cloned()
-                    .collect();
+        let today = chrono::Local::now().date_naive();
+        let search = Search::parse(search_text, today)?;
+        let mut matches = HashMap::new();
+        let mut found = Vec::new();
+        for task in &self.tasks.list {
+            if let Some(found_match) = search.matches(task) {
+                matches.insert(task.id, found_match);
+                found.push(task.clone());
             }
         }
+        Ok((found, matches))
+    }
 
-        self.tasks
-            .list
-            .iter()
-            .filter(|t| t.title.contains(search_text))
-            .cloned()
-            .collect()
+    // All tasks, one entry per configured status, overdue tasks and the saved searches
+    fn get_filter_options(config: &Config) -> Vec<(String, Option<StatusFilter>)> {
+        let mut options = vec![("All".to_string(), None)];
+        options.extend(crate::tasks::statuses().iter().map(|status| {
+            let filter = StatusFilter::Status(TaskStatus::from(status.name.clone()));
+            (status.name.clone(), Some(filter))
+        }));
+        options.push(("Overdue".to_string(), Some(StatusFilter::Overdue)));
+        options.extend(
+            config
+                .saved_searches
+                .iter()
+                .map(|saved| (saved.name.clone(), Some(StatusFilter::Saved(saved.clone())))),
+        );
+        options
+    }
+
+    fn apply_filter(&mut self, index: usize) {
+        let mut options = Self::get_filter_options(&self.config);
+        if index < options.len() {
+            self.filter.state.select(Some(index));
+            self.filter.mode = options.swap_remove(index).1;
+        }
+        self.search_error = None;
+        self.refresh_task_list();
+        self.state.select(None);
+    }
+
+    // Number keys pick the filters in the order of the filter popup
+    fn filter_number(key: &KeyEvent) -> Option<usize> {
+        match (key.code, key.modifiers) {
+            (KeyCode::Char(c @ '1'..='9'), KeyModifiers::NONE) => {
+                c.to_digit(10).map(|n| n as usize - 1)
+            }
+            _ => None,
+        }
     }
 
     fn get_border_style(&self, focus: AppFocus) -> Style {
+        let theme = crate::theme::current();
         if self.focus == focus {
-            PRIMARY_STYLE
+            theme.primary.into()
         } else {
-            SECONDARY_STYLE
+            theme.secondary.into()
         }
     }
 
-    fn group_date_tasks(
+    fn group_tasks(
         tasks: &[Task],
-    ) -> (Vec<(usize, u128)>, BTreeMap<NaiveDate, Vec<Task>>, usize) {
-        let mut grouped_tasks: BTreeMap<NaiveDate, Vec<Task>> = BTreeMap::new();
+        sort: SortMode,
+        matches: &HashMap<u128, SearchMatch>,
+        collapsed: &HashSet<String>,
+    ) -> GroupedTasks {
+        let today = chrono::Local::now().date_naive();
+        let mut tasks = tasks.to_vec();
+        // Sorts are stable, ties keep the order of the due dates
+        tasks.sort_by_key(|task| task.due_key());
+        match sort {
+            SortMode::DueAscending | SortMode::None => {}
+            SortMode::Buckets => tasks.sort_by_key(|task| Self::bucket(task, today)),
+            // Tasks without a date stay last
+            SortMode::DueDescending => tasks.sort_by_key(|task| {
+                let due = task.due();
+                (due.is_none(), Reverse(due))
+            }),
+            SortMode::Status => tasks.sort_by_key(|task| {
+                let statuses = crate::tasks::statuses();
+                let name = task.status.as_str();
+                // Statuses removed from the config come last
+                let position = statuses.iter().position(|status| status.name == name);
+                (position.unwrap_or(statuses.len()), name.to_string())
+            }),
+            SortMode::Title => tasks.sort_by_key(|task| task.title.to_lowercase()),
+            SortMode::Created => tasks.sort_by_key(|task| Reverse(task.created_at)),
+            SortMode::Updated => tasks.sort_by_key(|task| Reverse(task.updated_at)),
+            SortMode::Completed => tasks.sort_by_key(|task| Reverse(task.completed_at)),
+        }
+
+        let mut groups: Vec<Group> = Vec::new();
         for task in tasks {
-            let date = NaiveDate::parse_from_str(&task.date, "%d %m %Y").unwrap();
-            grouped_tasks.entry(date).or_default().push(task.clone());
+            let title = match sort {
+                SortMode::DueAscending | SortMode::DueDescending => Some(task.day().map_or_else(
+                    || "Someday".to_string(),
+                    |date| Self::date_title(date, today),
+                )),
+                SortMode::Buckets => Some(Self::bucket(&task, today).to_string()),
+                SortMode::Status => Some(task.status.as_str().to_string()),
+                _ => None,
+            };
+            match groups.last_mut() {
+                Some(group) if group.title == title => group.tasks.push(task),
+                _ => groups.push(Group {
+                    collapsed: title
+                        .as_ref()
+                        .is_some_and(|title| collapsed.contains(title)),
+                    title,
+                    tasks: vec![task],
+                }),
+            }
         }
 
-        // Sort by time
-        for (_, task_list) in grouped_tasks.iter_mut() {
-            task_list.sort_by_key(|task| NaiveTime::parse_from_str(&task.time, "%H %M").unwrap());
+        // The best matches of a search come first, ties keep the order of the sort
+        if !matches.is_empty() {
+            for group in &mut groups {
+                group
+                    .tasks
+                    .sort_by_key(|task| Reverse(matches.get(&task.id).map(|m| m.score)));
+            }
         }
 
         let mut selectable: Vec<(usize, u128)> = Vec::new();
         let mut idx = 0;
 
-        for (_, tasks) in &grouped_tasks {
-            idx += 1;
-            for task in tasks {
+        for group in &groups {
+            if group.title.is_some() {
+                idx += 1;
+            }
+            if group.collapsed {
+                continue;
+            }
+            for task in &group.tasks {
                 selectable.push((idx, task.id));
                 idx += 1;
             }
         }
-        (selectable, grouped_tasks, idx)
+        (selectable, groups, idx)
+    }
+
+    // Open tasks past their due time are overdue, done ones are in Earlier
+    fn bucket(task: &Task, today: NaiveDate) -> Bucket {
+        let date = task.day();
+        let end_of_week = today
+            .checked_add_days(Days::new(
+                (6 - today.weekday().num_days_from_monday()).into(),
+            ))
+            .unwrap_or(today);
+        match date {
+            _ if task.is_overdue() => Bucket::Overdue,
+            None => Bucket::Someday,
+            Some(date) if date < today => Bucket::Earlier,
+            Some(date) if date == today => Bucket::Today,
+            Some(date) if Some(date) == today.succ_opt() => Bucket::Tomorrow,
+            Some(date) if date <= end_of_week => Bucket::ThisWeek,
+            Some(_) => Bucket::Later,
+        }
+    }
+
+    // Format the date header based on its relation to today
+    fn date_title(date: NaiveDate, today: NaiveDate) -> String {
+        if date == today {
+            "Today".to_string()
+        } else if Some(date) == today.succ_opt() {
+            "Tomorrow".to_string()
+        } else {
+            date.format("%a %b %d %Y").to_string()
+        }
     }
 
     // Verify that the preview scroll is within bounds
@@ -412,56 +1082,161 @@ impl App<'_> {
     }
 
     pub fn handle_key(&mut self, key: KeyEvent) -> bool {
-        if matches!(self.focus, AppFocus::LeftArea | AppFocus::RightArea) {
-            if key.code == KeyCode::Char('q') {
-                return true;
+        self.vim.message = None;
+        if self.focus == AppFocus::LeftArea && self.config.vim_mode {
+            let actions = [Action::Up, Action::Down, Action::Command];
+            let navigation = self.config.keybindings.action(&key, &actions);
+            if let Some(action) = self.vim.handle_key(key, navigation) {
+                self.handle_vim_action(action);
+                return false;
             }
         }
+
+        let keys = &self.config.keybindings;
         match self.focus {
-            AppFocus::LeftArea => match key.code {
-                KeyCode::BackTab | KeyCode::Tab => {
+            AppFocus::LeftArea => match keys.action(&key, LIST_ACTIONS) {
+                Some(Action::Quit) => return true,
+                Some(Action::SwitchFocus) => {
                     self.focus = AppFocus::RightArea;
                     if self.right_area != RightArea::Preview {
                         self.new_task.quit = false;
                     }
                 }
-                KeyCode::Down => self.scroll(ScrollDirection::Down),
-                KeyCode::Up => self.scroll(ScrollDirection::Up),
-                KeyCode::Esc => self.select_none(),
-                KeyCode::Char(' ') => self.toggle_completed(),
-                KeyCode::Char('t') => self.theme = self.theme.change_theme(),
-                KeyCode::Char('d') => {
-                    if let Some(_) = self.get_selected() {
-                        self.focus = AppFocus::DeletePrompt
-                    }
+                Some(Action::Down) if self.view == View::Board => self.board_step(0, 1),
+                Some(Action::Up) if self.view == View::Board => self.board_step(0, -1),
+                Some(Action::Left) if self.view == View::Board => self.board_step(-1, 0),
+                Some(Action::Right) if self.view == View::Board => self.board_step(1, 0),
+                Some(Action::MoveLeft) if self.view == View::Board => self.move_task(-1),
+                Some(Action::MoveRight) if self.view == View::Board => self.move_task(1),
+                Some(Action::Down) => self.scroll(ScrollDirection::Down),
+                Some(Action::Up) => self.scroll(ScrollDirection::Up),
+                Some(Action::Left) if self.view == View::Agenda => {
+                    self.change_week(ScrollDirection::Up)
                 }
-                KeyCode::Char('e') => {
-                    if key.modifiers.contains(KeyModifiers::CONTROL) {
-                        self.focus = AppFocus::ToggleEnc;
-                        return false;
-                    }
-                    if let Some(task) = self.get_selected() {
-                        self.new_task = NewTask::from(task);
-                        self.focus = AppFocus::RightArea;
-                        self.save_new_task_state();
-                        self.right_area = RightArea::EditTask;
+                Some(Action::Right) if self.view == View::Agenda => {
+                    self.change_week(ScrollDirection::Down)
+                }
+                Some(Action::Agenda) => {
+                    self.view = match self.view {
+                        View::Agenda => View::List,
+                        _ => View::Agenda,
+                    };
+                    self.show_selected_week();
+                }
+                Some(Action::Board) => {
+                    self.view = match self.view {
+                        View::Board => View::List,
+                        _ => View::Board,
+                    };
+                }
+                Some(Action::Deselect)
+                    if self.state.selected().is_none() && self.filter.date.is_some() =>
+                {
+                    self.filter.date = None;
+                    self.refresh_task_list();
+                }
+                Some(Action::Deselect) => self.select_none(),
+                Some(Action::ToggleCompleted) => self.toggle_completed(),
+                Some(Action::ToggleIcons) => self.ascii_icons = !self.ascii_icons,
+                Some(Action::NarrowList) => {
+                    self.list_width = self.list_width.saturating_sub(5).max(MIN_LIST_WIDTH)
+                }
+                Some(Action::WidenList) => {
+                    self.list_width = (self.list_width + 5).min(MAX_LIST_WIDTH)
+                }
+                Some(Action::Delete) if self.get_selected().is_some() => {
+                    self.focus = AppFocus::DeletePrompt
+                }
+                Some(Action::ToggleEncryption) => self.focus = AppFocus::ToggleEnc,
+                Some(Action::Edit) => self.edit_selected(),
+                Some(Action::Preview) => self.right_area = RightArea::Preview,
+                Some(Action::Filter) => self.focus = AppFocus::Filter,
+                Some(Action::Sort) => {
+                    self.sort = self.sort.next();
+                    self.refresh_task_list();
+                    self.state.select(None);
+                }
+                Some(Action::Calendar) => {
+                    let date = self.get_selected().and_then(|task| task.due());
+                    if let Some(date) = self.filter.date.or(date.map(|due| due.date())) {
+                        self.calendar.select(date);
                     }
+                    self.focus = AppFocus::Calendar;
+                }
+                Some(Action::Workspaces) => {
+                    let current = self.config.workspace_name();
+                    let index = self
+                        .config
+                        .workspace_names()
+                        .iter()
+                        .position(|name| *name == current);
+                    self.workspaces.select(index);
+                    self.focus = AppFocus::Workspace;
                 }
-                KeyCode::Char('p') => self.right_area = RightArea::Preview,
-                KeyCode::Char('f') => self.focus = AppFocus::Filter,
-                KeyCode::Char('n') => {
+                Some(Action::Themes) => {
+                    let names = crate::theme::names();
+                    let current = match (&self.theme, &self.config.theme) {
+                        (Some(name), _) | (None, Some(ThemeConfig::Name(name))) => Some(name),
+                        _ => None,
+                    };
+                    let index =
+                        current.and_then(|current| names.iter().position(|name| name == current));
+                    self.themes = ThemePicker {
+                        state: ListState::default().with_selected(index),
+                        names,
+                        previous: crate::theme::current(),
+                    };
+                    self.focus = AppFocus::Theme;
+                }
+                Some(Action::NewTask) => {
                     self.restore_new_task_state();
                     self.new_task.quit = false;
                     self.focus = AppFocus::RightArea;
                     self.right_area = RightArea::NewTask;
                     self.select_none();
                 }
-                KeyCode::Char('/') => self.focus = AppFocus::Search,
-                _ => {}
+                Some(Action::Search) => self.focus = AppFocus::Search,
+                Some(Action::QuickAdd) => {
+                    self.quick_add = TextArea::default();
+                    self.quick_add.set_cursor_line_style(Style::default());
+                    self.focus = AppFocus::QuickAdd;
+                }
+                Some(Action::ToggleGroup) => {
+                    if let Some(title) = self.selected_group() {
+                        self.toggle_group(title);
+                    }
+                }
+                Some(Action::ToggleAllGroups) => {
+                    if self.collapsed.is_empty() {
+                        let titles = self.tasks.grouped.iter().filter_map(|g| g.title.clone());
+                        self.collapsed.extend(titles);
+                    } else {
+                        self.collapsed.clear();
+                    }
+                    self.regroup();
+                }
+                Some(Action::SaveSearch) => {
+                    if self.search.lines()[0].trim().is_empty() {
+                        self.vim.message = Some("Type a search to save first".to_string());
+                    } else if self.search_error.is_some() {
+                        self.vim.message = Some("Fix the search before saving it".to_string());
+                    } else {
+                        self.search_name = TextArea::default();
+                        self.search_name.set_cursor_line_style(Style::default());
+                        self.focus = AppFocus::SaveSearch;
+                    }
+                }
+                _ => {
+                    if !self.config.vim_mode
+                        && let Some(index) = Self::filter_number(&key)
+                    {
+                        self.apply_filter(index);
+                    }
+                }
             },
             AppFocus::RightArea => {
                 if self.right_area != RightArea::Preview {
-                    self.new_task.handle_key(key);
+                    self.new_task.handle_key(key, keys);
                     if self.new_task.quit {
                         if self.new_task.completed {
                             self.add_or_modify_task();
@@ -477,108 +1252,515 @@ impl App<'_> {
                         self.focus = AppFocus::LeftArea;
                     }
                 } else {
-                    match key.code {
-                        KeyCode::BackTab | KeyCode::Tab => self.focus = AppFocus::LeftArea,
-                        KeyCode::Down => self.scroll_preview_down(),
-                        KeyCode::Up => self.scroll_preview_up(),
+                    let actions = [Action::Quit, Action::SwitchFocus, Action::Down, Action::Up];
+                    match keys.action(&key, &actions) {
+                        Some(Action::Quit) => return true,
+                        Some(Action::SwitchFocus) => self.focus = AppFocus::LeftArea,
+                        Some(Action::Down) => self.scroll_preview_down(),
+                        Some(Action::Up) => self.scroll_preview_up(),
                         _ => {}
                     }
                 }
             }
-            AppFocus::DeletePrompt => match key.code {
-                KeyCode::Char('y') => {
-                    self.delete_entry();
-                    self.focus = AppFocus::LeftArea;
+            AppFocus::DeletePrompt | AppFocus::FirstTimeSetup | AppFocus::ToggleEnc => {
+                if let Some(action) = keys.action(&key, &[Action::Yes, Action::No]) {
+                    self.answer_prompt(action == Action::Yes);
                 }
-                KeyCode::Char('n') => self.focus = AppFocus::LeftArea,
-                _ => {}
-            },
-            AppFocus::Search => match key.code {
-                KeyCode::Esc => self.focus = AppFocus::LeftArea,
-                KeyCode::Enter => self.focus = AppFocus::LeftArea,
-                KeyCode::BackTab | KeyCode::Tab => self.focus = AppFocus::LeftArea,
-                _ => {
+            }
+            AppFocus::Search => {
+                let actions = [Action::Cancel, Action::Confirm, Action::SwitchFocus];
+                if keys.action(&key, &actions).is_some() {
+                    self.focus = AppFocus::LeftArea;
+                } else {
                     self.search.input(key);
-                    let searched_tasks = self.get_filtered_tasks();
-                    let group = Self::group_date_tasks(&searched_tasks);
-                    self.tasks.selectable = group.0;
-                    self.tasks.grouped = group.1;
-                    self.total = group.2;
+                    self.apply_search();
                     self.state.select(None);
                 }
+            }
+            AppFocus::OverDue => {
+                if self.over_due.handle_key(key, keys) {
+                    self.focus = AppFocus::LeftArea;
+                }
+            }
+            AppFocus::Filter => match keys.action(&key, POPUP_ACTIONS) {
+                Some(Action::Confirm) => {
+                    let index = self.filter.state.selected().unwrap_or_default();
+                    self.apply_filter(index);
+                    self.focus = AppFocus::LeftArea;
+                }
+                Some(Action::Cancel | Action::Quit) => self.focus = AppFocus::LeftArea,
+                Some(Action::Down) => self.filter.state.select_next(),
+                Some(Action::Up) => self.filter.state.select_previous(),
+                _ => {
+                    if let Some(index) = Self::filter_number(&key) {
+                        self.apply_filter(index);
+                        self.focus = AppFocus::LeftArea;
+                    }
+                }
             },
-            AppFocus::FirstTimeSetup => match key.code {
-                KeyCode::Char('y') => {
-                    self.config.encryption = true;
-                    self.config.save();
-                    crate::auth::generate_key();
-                    self.update_task_list();
+            AppFocus::Calendar => match self.calendar.handle_key(key, keys) {
+                CalendarAction::None => {}
+                CalendarAction::Close => self.focus = AppFocus::LeftArea,
+                CalendarAction::Filter(date) => {
+                    self.filter.date = Some(date);
+                    self.refresh_task_list();
+                    self.state.select(None);
                     self.focus = AppFocus::LeftArea;
                 }
-                KeyCode::Char('n') => {
-                    self.config.save();
+                CalendarAction::NewTask(date) => {
+                    self.new_task = NewTask::with_date(date);
+                    self.focus = AppFocus::RightArea;
+                    self.right_area = RightArea::NewTask;
+                    self.select_none();
+                }
+            },
+            AppFocus::Workspace => match keys.action(&key, POPUP_ACTIONS) {
+                Some(Action::Confirm) => {
+                    let names = self.config.workspace_names();
+                    let selected = self.workspaces.selected().and_then(|i| names.get(i));
+                    if let Some(name) = selected.map(|name| name.to_string()) {
+                        self.switch_workspace(&name);
+                    }
                     self.focus = AppFocus::LeftArea;
                 }
+                Some(Action::Cancel | Action::Quit) => self.focus = AppFocus::LeftArea,
+                Some(Action::Down) => self.workspaces.select_next(),
+                Some(Action::Up) => self.workspaces.select_previous(),
                 _ => {}
             },
-            AppFocus::OverDue => {
-                if self.over_due.handle_key(key) {
+            AppFocus::Theme => match keys.action(&key, POPUP_ACTIONS) {
+                Some(Action::Confirm) => {
+                    let selected = self.themes.state.selected();
+                    if let Some(name) = selected.and_then(|i| self.themes.names.get(i)) {
+                        self.theme = Some(name.clone());
+                    }
                     self.focus = AppFocus::LeftArea;
                 }
-            }
-            AppFocus::ToggleEnc => match key.code {
-                KeyCode::Char('y') => {
-                    self.config.encryption = !self.config.encryption;
-                    self.config.save();
-                    self.focus = AppFocus::LeftArea
+                Some(Action::Cancel | Action::Quit) => {
+                    crate::theme::set(self.themes.previous.clone());
+                    self.focus = AppFocus::LeftArea;
                 }
-                KeyCode::Char('n') => self.focus = AppFocus::LeftArea,
-                _ => {}
+                Some(action) => {
+                    match action {
+                        Action::Down => self.themes.state.select_next(),
+                        _ => self.themes.state.select_previous(),
+                    }
+                    // Preview the theme while moving through the list
+                    let selected = self.themes.state.selected();
+                    let name = selected.and_then(|i| self.themes.names.get(i));
+                    if let Some(theme) = name.and_then(|name| crate::theme::load(name)) {
+                        crate::theme::set(theme);
+                    }
+                }
+                None => {}
             },
-            AppFocus::Filter => match key.code {
-                KeyCode::Enter => {
-                    self.filter.state.selected().map(|i| {
-                        self.filter.mode = match i {
-                            0 => None,
-                            1 => Some(TaskStatus::Completed),
-                            2 => Some(TaskStatus::Pending),
-                            3 => Some(TaskStatus::OverDue),
-                            _ => None,
-                        };
-                    });
-                    self.update_task_list();
-                    self.state.select(None);
+            AppFocus::SaveSearch => match keys.action(&key, &[Action::Cancel, Action::Confirm]) {
+                Some(Action::Cancel) => self.focus = AppFocus::LeftArea,
+                Some(Action::Confirm) => {
+                    let name = self.search_name.lines()[0].trim().to_string();
+                    if !name.is_empty() {
+                        self.vim.message = Some(format!("Saved \"{}\" to the filters", name));
+                        self.config.save_search(SavedSearch {
+                            name,
+                            query: self.search.lines()[0].trim().to_string(),
+                        });
+                        self.config.save();
+                        self.focus = AppFocus::LeftArea;
+                    }
+                }
+                _ => {
+                    self.search_name.input(key);
+                }
+            },
+            AppFocus::QuickAdd => match keys.action(&key, &[Action::Cancel, Action::Confirm]) {
+                Some(Action::Cancel) => self.focus = AppFocus::LeftArea,
+                Some(Action::Confirm) => {
+                    let today = chrono::Local::now().date_naive();
+                    let parsed = QuickAdd::parse(&self.quick_add.lines()[0], today);
+                    if !parsed.title.is_empty() {
+                        self.add_quick_task(parsed);
+                        self.focus = AppFocus::LeftArea;
+                    }
+                }
+                _ => {
+                    self.quick_add.input(key);
+                }
+            },
+            AppFocus::Command => match keys.action(&key, &[Action::Cancel, Action::Confirm]) {
+                Some(Action::Cancel) => self.focus = AppFocus::LeftArea,
+                Some(Action::Confirm) => {
                     self.focus = AppFocus::LeftArea;
+                    match self.run_command(&self.vim.command()) {
+                        Ok(quit) => return quit,
+                        Err(message) => self.vim.message = Some(message),
+                    }
+                }
+                _ => {
+                    self.vim.command_line.input(key);
                 }
-                KeyCode::Esc | KeyCode::Char('q') => self.focus = AppFocus::LeftArea,
-                KeyCode::Down => self.filter.state.select_next(),
-                KeyCode::Up => self.filter.state.select_previous(),
-                _ => {}
             },
         }
         false
     }
 
-    fn select_added_task(&mut self, task_id: u128) {
-        let idx = self
+    // Yes or no to the prompt that has the focus
+    fn answer_prompt(&mut self, yes: bool) {
+        match self.focus {
+            AppFocus::DeletePrompt if yes => self.delete_entry(),
+            AppFocus::FirstTimeSetup if yes => {
+                let previous = self.config.clone();
+                self.config.set_encryption(true);
+                crate::auth::generate_key();
+                if self.open_storage(previous) {
+                    self.update_task_list(Change::All("Enable encryption"));
+                }
+                self.config.save();
+            }
+            AppFocus::FirstTimeSetup => self.config.save(),
+            AppFocus::ToggleEnc if yes => {
+                let previous = self.config.clone();
+                let encryption = !self.config.encryption();
+                self.config.set_encryption(encryption);
+                if self.open_storage(previous) {
+                    self.config.save();
+                    self.update_task_list(Change::All(if encryption {
+                        "Enable encryption"
+                    } else {
+                        "Disable encryption"
+                    }));
+                }
+            }
+            _ => {}
+        }
+        self.focus = AppFocus::LeftArea;
+    }
+
+    /// Clicks select tasks, focus the search and answer prompts, a double click edits a task.
+    /// The wheel scrolls the task list or the preview.
+    pub fn handle_mouse(&mut self, mouse: MouseEvent) {
+        let position = Position::new(mouse.column, mouse.row);
+        let left_click = mouse.kind == MouseEventKind::Down(MouseButton::Left);
+        if let Some(confirm) = self.confirm_prompt() {
+            if let Some(yes) = confirm
+                .answer_at(self.areas.main, position)
+                .filter(|_| left_click)
+            {
+                self.answer_prompt(yes);
+            }
+            return;
+        }
+        if !matches!(
+            self.focus,
+            AppFocus::LeftArea | AppFocus::RightArea | AppFocus::Search
+        ) {
+            return;
+        }
+
+        let in_list = self.areas.list.contains(position);
+        let in_preview =
+            self.areas.right.contains(position) && self.right_area == RightArea::Preview;
+        match mouse.kind {
+            MouseEventKind::ScrollDown if in_list => self.scroll_list(ScrollDirection::Down),
+            MouseEventKind::ScrollUp if in_list => self.scroll_list(ScrollDirection::Up),
+            MouseEventKind::ScrollDown if in_preview => self.scroll_preview_down(),
+            MouseEventKind::ScrollUp if in_preview => self.scroll_preview_up(),
+            _ if !left_click => {}
+            _ if self.areas.search.contains(position) => {
+                self.leave_right_area();
+                self.focus = AppFocus::Search;
+            }
+            _ if self.areas.right.contains(position) && self.focus != AppFocus::RightArea => {
+                self.focus = AppFocus::RightArea;
+                if self.right_area != RightArea::Preview {
+                    self.new_task.quit = false;
+                }
+            }
+            _ if in_list => {
+                self.leave_right_area();
+                self.focus = AppFocus::LeftArea;
+                self.click_task(position);
+            }
+            _ => {}
+        }
+    }
+
+    // Selects the clicked task of the table, edits it on a double click
+    fn click_task(&mut self, position: Position) {
+        // The list area includes the borders
+        if self.view != View::List || !self.areas.table.contains(position) {
+            return;
+        }
+        let line = position.y.saturating_sub(self.areas.table.y) as usize;
+        let Some(row) = self.areas.table_lines.get(line).copied().flatten() else {
+            return;
+        };
+        // Headers can't be selected, clicking them collapses or expands the group
+        if !self.tasks.selectable.iter().any(|(i, _)| *i == row) {
+            if let Some(title) = self.group_at(row) {
+                self.toggle_group(title);
+            }
+            return;
+        }
+
+        let double_click = self
+            .last_click
+            .is_some_and(|(time, last)| last == row && time.elapsed() < DOUBLE_CLICK);
+        self.last_click = Some((Instant::now(), row));
+        self.select_row(row);
+        if double_click {
+            self.edit_selected();
+        }
+    }
+
+    // Title of the group with the selected task
+    fn selected_group(&self) -> Option<String> {
+        let id = self.get_selected()?.id;
+        self.tasks
+            .grouped
+            .iter()
+            .find(|group| group.tasks.iter().any(|task| task.id == id))?
+            .title
+            .clone()
+    }
+
+    // Title of the group whose header is at `row` of the table
+    fn group_at(&self, row: usize) -> Option<String> {
+        let mut header = 0;
+        for group in &self.tasks.grouped {
+            let title = group.title.as_ref()?;
+            if header == row {
+                return Some(title.clone());
+            }
+            header += 1 + if group.collapsed {
+                0
+            } else {
+                group.tasks.len()
+            };
+        }
+        None
+    }
+
+    // A selected task that gets hidden passes the selection to the next visible task
+    fn toggle_group(&mut self, title: String) {
+        if !self.collapsed.remove(&title) {
+            self.collapsed.insert(title.clone());
+        }
+        let selected = self.get_selected().map(|task| task.id);
+        let hidden = selected.is_some() && self.selected_group().as_ref() == Some(&title);
+        self.regroup();
+        if hidden && self.collapsed.contains(&title) {
+            let header = (0..self.total).find(|row| self.group_at(*row).as_ref() == Some(&title));
+            let next = self
+                .tasks
+                .selectable
+                .iter()
+                .find(|(row, _)| header.is_some_and(|header| *row > header))
+                .or(self.tasks.selectable.last())
+                .map(|(_, id)| *id);
+            self.select_by_id(next);
+        } else {
+            self.select_by_id(selected);
+        }
+        self.state_save = None;
+    }
+
+    // Groups the shown tasks again, keeping the search and the filter
+    fn regroup(&mut self) {
+        let selected = self.get_selected().map(|task| task.id);
+        let tasks: Vec<Task> = self
             .tasks
-            .selectable
+            .grouped
             .iter()
-            .find(|(_, id)| *id == task_id)
-            .unwrap();
-        self.state.select(Some(idx.0));
+            .flat_map(|group| group.tasks.iter().cloned())
+            .collect();
+        let group = Self::group_tasks(&tasks, self.sort, &self.tasks.matches, &self.collapsed);
+        self.tasks.selectable = group.0;
+        self.tasks.grouped = group.1;
+        self.total = group.2;
+        self.select_by_id(selected);
+    }
+
+    // Keeps an unsaved new task when the focus moves away from it
+    fn leave_right_area(&mut self) {
+        if self.focus == AppFocus::RightArea && self.right_area != RightArea::Preview {
+            self.save_new_task_state();
+        }
+    }
+
+    fn scroll_list(&mut self, direction: ScrollDirection) {
+        match (&self.view, direction) {
+            (View::Board, ScrollDirection::Up) => self.board_step(0, -1),
+            (View::Board, ScrollDirection::Down) => self.board_step(0, 1),
+            (_, direction) => self.scroll(direction),
+        }
+    }
+
+    fn edit_selected(&mut self) {
+        if let Some(task) = self.get_selected() {
+            self.new_task = NewTask::from(task);
+            self.focus = AppFocus::RightArea;
+            self.save_new_task_state();
+            self.right_area = RightArea::EditTask;
+        }
+    }
+
+    fn handle_vim_action(&mut self, action: VimAction) {
+        let position = self.state.selected().and_then(|row| {
+            self.tasks
+                .selectable
+                .iter()
+                .position(|(selectable, _)| *selectable == row)
+        });
+        let half_page = (self.page_height / 2).max(1) as isize;
+        let position = match action {
+            VimAction::Pending => return,
+            VimAction::Move(rows) if self.view == View::Board => {
+                return self.board_step(0, rows);
+            }
+            VimAction::Move(rows) => position.map_or(0, |i| i.saturating_add_signed(rows)),
+            VimAction::First => 0,
+            VimAction::Last => usize::MAX,
+            VimAction::Line(line) => line.saturating_sub(1),
+            VimAction::HalfPageDown => position.map_or(0, |i| i.saturating_add_signed(half_page)),
+            VimAction::HalfPageUp => position.map_or(0, |i| i.saturating_add_signed(-half_page)),
+            VimAction::Delete => {
+                if self.get_selected().is_some() {
+                    self.focus = AppFocus::DeletePrompt;
+                }
+                return;
+            }
+            VimAction::CommandLine => {
+                self.focus = AppFocus::Command;
+                return;
+            }
+        };
+
+        let last = self.tasks.selectable.len().saturating_sub(1);
+        if let Some(&(row, _)) = self.tasks.selectable.get(position.min(last)) {
+            self.select_row(row);
+        }
+    }
+
+    fn select_row(&mut self, row: usize) {
+        self.state.select(Some(row));
+        self.state_save = None;
+        self.show_selected_week();
+        if self.right_area == RightArea::EditTask
+            && let Some(task) = self.get_selected()
+        {
+            self.new_task = NewTask::from(task);
+        }
+    }
+
+    // Runs a `:` command, returns true to quit
+    fn run_command(&mut self, command: &str) -> Result<bool, String> {
+        let (name, argument) = match command.split_once(' ') {
+            Some((name, argument)) => (name, argument.trim()),
+            None => (command, ""),
+        };
+        match name {
+            "" => {}
+            "w" | "write" => self.update_task_list(Change::All("Save tasks")),
+            "q" | "quit" => return Ok(true),
+            "wq" | "x" => {
+                self.update_task_list(Change::All("Save tasks"));
+                return Ok(true);
+            }
+            "sort" => {
+                self.sort = SortMode::from_name(argument)
+                    .ok_or_else(|| format!("Unknown sort \"{}\"", argument))?;
+                self.refresh_task_list();
+                self.state.select(None);
+            }
+            "filter" => {
+                let options = Self::get_filter_options(&self.config);
+                let index = if argument.is_empty() {
+                    0
+                } else {
+                    options
+                        .iter()
+                        .position(|(option, _)| option.eq_ignore_ascii_case(argument))
+                        .ok_or_else(|| format!("Unknown filter \"{}\"", argument))?
+                };
+                self.apply_filter(index);
+            }
+            _ => match name.parse() {
+                Ok(line) => self.handle_vim_action(VimAction::Line(line)),
+                Err(_) => return Err(format!("Not a command: {}", command)),
+            },
+        }
+        Ok(false)
+    }
+
+    // An invalid search keeps the previous results and shows the error instead
+    fn apply_search(&mut self) {
+        let (searched_tasks, matches) = match self.get_filtered_tasks() {
+            Ok(found) => found,
+            Err(error) => {
+                self.search_error = Some(error);
+                return;
+            }
+        };
+        self.search_error = None;
+        let group = Self::group_tasks(&searched_tasks, self.sort, &matches, &self.collapsed);
+        self.tasks.matches = matches;
+        self.tasks.selectable = group.0;
+        self.tasks.grouped = group.1;
+        self.total = group.2;
+    }
+
+    // A filter or a collapsed group can hide the task
+    fn select_added_task(&mut self, task_id: u128) {
+        if let Some((idx, _)) = self.tasks.selectable.iter().find(|(_, id)| *id == task_id) {
+            self.state.select(Some(*idx));
+        }
     }
 
     fn add_or_modify_task(&mut self) {
-        let task = self.new_task.get_task().clone();
-        if self.right_area == RightArea::EditTask {
+        let mut task = self.new_task.get_task().clone();
+        let id = task.id;
+        let previous = self.tasks.list.iter().find(|t| t.id == id);
+        task.touch(previous);
+        let change = if self.right_area == RightArea::EditTask {
             if let Some(selected_task) = self.get_selected_mut() {
                 *selected_task = task;
             }
+            Change::Edited(id)
         } else {
             self.tasks.list.push(task);
-        }
-        self.update_task_list();
+            Change::Added(id)
+        };
+        self.update_task_list(change);
+    }
+
+    fn add_quick_task(&mut self, parsed: QuickAdd) {
+        let mut task = Task {
+            title: parsed.title,
+            date: parsed
+                .due
+                .date
+                .map(|date| date.format("%d %m %Y").to_string())
+                .unwrap_or_default(),
+            time: parsed
+                .due
+                .time
+                .map(|time| time.format("%H %M").to_string())
+                .unwrap_or_default(),
+            ..Task::new()
+        };
+        task.touch(None);
+        let id = task.id;
+        self.vim.message = Some(format!(
+            "Added \"{}\" {}",
+            task.title,
+            parsed.due.describe()
+        ));
+        self.tasks.list.push(task);
+        // Like a task from the editor, the new task is selected with the search cleared
+        self.search.select_all();
+        self.search.delete_newline();
+        self.update_task_list(Change::Added(id));
+        self.select_added_task(id);
     }
 
     fn scroll_preview_up(&mut self) {
@@ -630,77 +1812,221 @@ impl App<'_> {
         if self.tasks.selectable.is_empty() || self.select_last_selected() {
             return;
         }
-        let index = match self.state.selected() {
-            Some(index) => index,
-            None => 0,
+        let selected = self.state.selected();
+        let position = self
+            .tasks
+            .selectable
+            .iter()
+            .position(|(i, _)| Some(*i) == selected);
+        // Wraps around at both ends
+        let last = self.tasks.selectable.len() - 1;
+        let next = match (scroll_direction, position) {
+            (ScrollDirection::Up, Some(position)) if position > 0 => position - 1,
+            (ScrollDirection::Up, _) => last,
+            (ScrollDirection::Down, Some(position)) if position < last => position + 1,
+            (ScrollDirection::Down, _) => 0,
         };
-        let mut next = index;
-        match scroll_direction {
-            ScrollDirection::Up => {
-                next = next.saturating_sub(1);
-                while !self.tasks.selectable.iter().any(|&(i, _)| i == next) {
-                    next = next.saturating_sub(1);
-                    if next <= 0 {
-                        next = self.tasks.selectable.last().copied().unwrap_or((0, 0)).0;
-                    }
-                }
-            }
-            ScrollDirection::Down => {
-                next += 1;
-                while !self.tasks.selectable.iter().any(|&(i, _)| i == next) {
-                    next += 1;
-                    if next >= self.total {
-                        next = self.tasks.selectable[0].0;
-                    }
-                }
-            }
-        }
 
-        self.state.select(Some(next));
+        self.state.select(Some(self.tasks.selectable[next].0));
+        self.show_selected_week();
 
         if self.right_area == RightArea::EditTask {
             self.new_task = NewTask::from(self.get_selected().unwrap());
         }
     }
 
+    // The agenda follows the selection, so that scrolling moves across weeks
+    fn show_selected_week(&mut self) {
+        if let Some(due) = self.get_selected().and_then(|task| task.due()) {
+            self.agenda.show_week_of(due.date());
+        }
+    }
+
+    fn change_week(&mut self, direction: ScrollDirection) {
+        match direction {
+            ScrollDirection::Up => self.agenda.previous_week(),
+            ScrollDirection::Down => self.agenda.next_week(),
+        }
+        // Select the first task of the week so that edit and delete keep working
+        let first = self.week_tasks().first().map(|task| task.id);
+        self.select_by_id(first);
+        self.state_save = None;
+    }
+
+    // Shown tasks due within the week of the agenda
+    fn week_tasks(&self) -> Vec<Task> {
+        let week = self.agenda.start()..=self.agenda.end();
+        let mut tasks: Vec<Task> = self
+            .tasks
+            .grouped
+            .iter()
+            .flat_map(|group| &group.tasks)
+            .filter(|task| task.due().is_some_and(|due| week.contains(&due.date())))
+            .cloned()
+            .collect();
+        tasks.sort_by_key(|task| task.due_key());
+        tasks
+    }
+
+    fn select_by_id(&mut self, id: Option<u128>) {
+        let index = id.and_then(|id| {
+            self.tasks
+                .selectable
+                .iter()
+                .find(|(_, task_id)| *task_id == id)
+                .map(|(i, _)| *i)
+        });
+        self.state.select(index);
+    }
+
+    fn update_board(&mut self) {
+        let tasks: Vec<Task> = self
+            .tasks
+            .grouped
+            .iter()
+            .flat_map(|group| group.tasks.iter().cloned())
+            .collect();
+        let selected = self.get_selected().map(|task| task.id);
+        self.board.set_tasks(&tasks, selected);
+    }
+
+    fn board_step(&mut self, columns: isize, rows: isize) {
+        self.update_board();
+        if let Some(id) = self.board.step(columns, rows) {
+            self.select_by_id(Some(id));
+            self.state_save = None;
+        }
+        if self.right_area == RightArea::EditTask
+            && let Some(task) = self.get_selected()
+        {
+            self.new_task = NewTask::from(task);
+        }
+    }
+
+    // Moves the selected task to the next column of the board
+    fn move_task(&mut self, direction: isize) {
+        let Some(task) = self.get_selected() else {
+            return;
+        };
+        self.update_board();
+        if let Some(status) = self.board.next_status(direction) {
+            self.set_selected_status(status);
+            self.select_by_id(Some(task.id));
+        }
+    }
+
     fn select_none(&mut self) {
         self.state_save = Some(self.state.clone());
         self.state.select(None);
     }
 
-    fn update_task_list(&mut self) {
-        let display_list = Self::get_display_tasks(&self.tasks.list, &self.filter.mode);
-        let grouped_tasks = Self::group_date_tasks(&display_list);
+    fn switch_workspace(&mut self, name: &str) {
+        if name == self.config.workspace_name() {
+            return;
+        }
+        // Each workspace remembers its own filter, search and selection
+        self.save_state();
+        let previous = self.config.clone();
+        self.config.select_workspace(name);
+        // A workspace that can't be read is left closed, the current one stays open
+        let loaded = crate::storage::open(&self.config)
+            .and_then(|storage| Ok((Self::load_tasks(storage.as_ref())?, storage)));
+        let (tasks, storage) = match loaded {
+            Ok(loaded) => loaded,
+            Err(error) => {
+                self.config = previous;
+                self.vim.message = Some(format!("Failed to open workspace {}: {}", name, error));
+                return;
+            }
+        };
+        self.config.save();
+        self.storage = storage;
+        self.watcher = TaskWatcher::new(&self.storage.path()).ok();
+        self.tasks.list = tasks;
+        self.refresh_task_list();
+        self.state.select(None);
+        self.state_save = None;
+    }
+
+    // An invalid saved search shows no tasks and its error below the search box
+    fn refresh_task_list(&mut self) {
+        let display_list =
+            Self::get_display_tasks(&self.tasks.list, &self.filter.mode, self.filter.date)
+                .unwrap_or_else(|error| {
+                    self.search_error = Some(error);
+                    Vec::new()
+                });
+        let grouped_tasks =
+            Self::group_tasks(&display_list, self.sort, &HashMap::new(), &self.collapsed);
+        self.tasks.matches.clear();
         self.tasks.selectable = grouped_tasks.0;
         self.tasks.grouped = grouped_tasks.1;
         self.total = grouped_tasks.2;
-        if self.config.encryption {
-            crate::tasks::save_encrypted(&self.tasks.list).unwrap();
-        } else {
-            crate::tasks::save(&self.tasks.list).unwrap();
+    }
+
+    fn update_task_list(&mut self, change: Change) {
+        self.refresh_task_list();
+        let list = &self.tasks.list;
+        let find = |id: u128| list.iter().find(|t| t.id == id);
+        let (saved, message) = match &change {
+            Change::Added(id) | Change::Edited(id) | Change::StatusChanged(id) => {
+                let Some(task) = find(*id) else {
+                    return;
+                };
+                let message = match change {
+                    Change::Added(_) => format!("Add task \"{}\"", task.title),
+                    Change::Edited(_) => format!("Edit task \"{}\"", task.title),
+                    _ if task.status.is_done() => format!("Complete task \"{}\"", task.title),
+                    _ if task.status == TaskStatus::open() => {
+                        format!("Reopen task \"{}\"", task.title)
+                    }
+                    _ => format!("Move task \"{}\" to {}", task.title, task.status.as_str()),
+                };
+                (self.storage.save_task(list, task), message)
+            }
+            Change::Deleted(task) => (
+                self.storage.delete_task(list, task.id),
+                format!("Delete task \"{}\"", task.title),
+            ),
+            Change::All(reason) => (self.storage.save(list), reason.to_string()),
+        };
+
+        // The change stays in the list, the next save may still succeed
+        if let Err(error) = saved {
+            self.vim.message = Some(format!("Failed to save the tasks: {}", error));
+            return;
+        }
+        if crate::sync::git_config(&self.config).is_some() {
+            self.committer.commit(self.storage.path(), message);
         }
     }
 
     fn toggle_completed(&mut self) {
-        if let Some(task) = self.get_selected_mut() {
-            if task.status == TaskStatus::Completed {
-                if task.is_overdue() {
-                    task.status = TaskStatus::OverDue;
-                } else {
-                    task.status = TaskStatus::Pending;
-                }
+        if let Some(task) = self.get_selected() {
+            let status = if task.status.is_done() {
+                TaskStatus::open()
             } else {
-                task.status = TaskStatus::Completed;
-            }
-            self.update_task_list();
+                TaskStatus::done()
+            };
+            self.set_selected_status(status);
+        }
+    }
+
+    fn set_selected_status(&mut self, status: TaskStatus) {
+        if let Some(task) = self.get_selected_mut() {
+            let id = task.id;
+            let previous = task.clone();
+            task.status = status;
+            task.touch(Some(&previous));
+            self.update_task_list(Change::StatusChanged(id));
         }
     }
 
     fn delete_entry(&mut self) {
         if let Some(task) = self.get_selected() {
             self.tasks.list.retain(|t| t.id != task.id);
-            self.update_task_list();
-            if let Some(_) = self.state.selected() {
+            self.update_task_list(Change::Deleted(task));
+            if self.state.selected().is_some() {
                 self.scroll(ScrollDirection::Down);
             }
         }
@@ -710,62 +2036,199 @@ impl App<'_> {
     }
 
     fn get_footer_text(&self) -> String {
-        let arrows = if self.theme == Theme::Default {
-            "[ ] Navigate"
-        } else {
-            "[Up/Down] Navigate"
-        };
+        let keys = &self.config.keybindings;
+        let navigate = keys.hint(&[Action::Up, Action::Down], "Navigate");
 
         let mut footer_text = Vec::new();
         match self.focus {
             AppFocus::LeftArea => {
-                footer_text.push(arrows);
+                footer_text.push(navigate);
                 if self.state.selected().is_some() {
-                    footer_text.extend_from_slice(&[
-                        "[e] Edit Task",
-                        "[d] Delete Task",
-                        "[Space] Toggle Completed",
+                    footer_text.extend([
+                        keys.hint(&[Action::Edit], "Edit Task"),
+                        keys.hint(&[Action::Delete], "Delete Task"),
+                        keys.hint(&[Action::ToggleCompleted], "Toggle Completed"),
                     ]);
                 }
-                footer_text.push("[f] Filter");
-                footer_text.push("[c] Show completed");
-                footer_text.push("[n] New Task");
-                footer_text.push("[t] Compatibility Mode");
+                footer_text.push(keys.hint(&[Action::Filter], "Filter"));
+                if !self.search.lines()[0].trim().is_empty() {
+                    footer_text.push(keys.hint(&[Action::SaveSearch], "Save Search"));
+                }
+                footer_text.push(keys.hint(&[Action::Sort], "Sort"));
+                if self.view == View::List && self.tasks.grouped.iter().any(|g| g.title.is_some()) {
+                    let actions = [Action::ToggleGroup, Action::ToggleAllGroups];
+                    footer_text.push(keys.hint(&actions, "Collapse"));
+                }
+                footer_text.push(keys.hint(&[Action::Workspaces], "Workspaces"));
+                footer_text.push(keys.hint(&[Action::Themes], "Themes"));
+                let (agenda, board) = match self.view {
+                    View::List => ("Agenda", "Board"),
+                    View::Agenda => {
+                        footer_text.push(keys.hint(&[Action::Left, Action::Right], "Week"));
+                        ("List", "Board")
+                    }
+                    View::Board => {
+                        footer_text.push(keys.hint(&[Action::Left, Action::Right], "Column"));
+                        if self.state.selected().is_some() {
+                            let actions = [Action::MoveLeft, Action::MoveRight];
+                            footer_text.push(keys.hint(&actions, "Move Task"));
+                        }
+                        ("Agenda", "List")
+                    }
+                };
+                footer_text.push(keys.hint(&[Action::Agenda], agenda));
+                footer_text.push(keys.hint(&[Action::Board], board));
+                footer_text.push(keys.hint(&[Action::Calendar], "Calendar"));
+                if self.state.selected().is_none() && self.filter.date.is_some() {
+                    footer_text.push(keys.hint(&[Action::Deselect], "Clear Date"));
+                }
+                footer_text.push(keys.hint(&[Action::NewTask], "New Task"));
+                footer_text.push(keys.hint(&[Action::QuickAdd], "Quick Add"));
+                if self.config.vim_mode {
+                    footer_text.push(keys.hint(&[Action::Command], "Command"));
+                }
+                footer_text.push(keys.hint(&[Action::ToggleIcons], "Compatibility Mode"));
+                let actions = [Action::NarrowList, Action::WidenList];
+                footer_text.push(keys.hint(&actions, "Resize"));
                 if self.right_area != RightArea::Preview && self.state.selected().is_some() {
-                    footer_text.push("[p] Preview");
+                    footer_text.push(keys.hint(&[Action::Preview], "Preview"));
                 }
                 let title = match self.right_area {
-                    RightArea::EditTask => "[Tab] Focus Edit Task",
-                    RightArea::NewTask => "[Tab] Focus New Task",
-                    RightArea::Preview => "[Tab] Focus Preview",
+                    RightArea::EditTask => "Focus Edit Task",
+                    RightArea::NewTask => "Focus New Task",
+                    RightArea::Preview => "Focus Preview",
                 };
-                let enc = if self.config.encryption {
-                    "[C-e] Disable Encryption"
+                let enc = if self.config.encryption() {
+                    "Disable Encryption"
                 } else {
-                    "[C-e] Enable Encryption"
+                    "Enable Encryption"
                 };
-                footer_text.extend_from_slice(&[title, enc, "[q] Quit"]);
+                footer_text.extend([
+                    keys.hint(&[Action::SwitchFocus], title),
+                    keys.hint(&[Action::ToggleEncryption], enc),
+                    keys.hint(&[Action::Quit], "Quit"),
+                ]);
             }
             AppFocus::RightArea => {
                 if self.right_area != RightArea::Preview {
-                    footer_text = self.new_task.footer_text().to_vec();
+                    footer_text = self.new_task.footer_text(keys);
                 } else {
-                    footer_text.extend_from_slice(&[arrows, "[Tab] Focus Tasks", "[q] Quit"]);
+                    footer_text.extend([
+                        navigate,
+                        keys.hint(&[Action::SwitchFocus], "Focus Tasks"),
+                        keys.hint(&[Action::Quit], "Quit"),
+                    ]);
                 }
             }
             AppFocus::DeletePrompt | AppFocus::FirstTimeSetup | AppFocus::ToggleEnc => {
-                footer_text.extend_from_slice(&["[y] Yes", "[n] No"]);
+                footer_text.extend([
+                    keys.hint(&[Action::Yes], "Yes"),
+                    keys.hint(&[Action::No], "No"),
+                ]);
             }
             AppFocus::Search => {
-                footer_text.extend_from_slice(&["[Esc] Exit Search", "[Enter] Exit Search"]);
+                let actions = [Action::Cancel, Action::Confirm, Action::SwitchFocus];
+                footer_text.push(keys.hint(&actions, "Exit Search"));
             }
             AppFocus::OverDue => {
-                footer_text.extend_from_slice(&[arrows, "[q] Quit"]);
+                footer_text.extend([
+                    navigate,
+                    keys.hint(&[Action::Cancel, Action::Quit], "Close"),
+                ]);
             }
             AppFocus::Filter => {
-                footer_text.extend_from_slice(&[arrows, "[Enter] Apply", "[Esc][q] Cancel"]);
+                footer_text.extend([
+                    navigate,
+                    keys.hint(&[Action::Confirm], "Apply"),
+                    keys.hint(&[Action::Cancel, Action::Quit], "Cancel"),
+                ]);
+            }
+            AppFocus::Workspace => {
+                footer_text.extend([
+                    navigate,
+                    keys.hint(&[Action::Confirm], "Switch"),
+                    keys.hint(&[Action::Cancel, Action::Quit], "Cancel"),
+                ]);
+            }
+            AppFocus::Theme => {
+                footer_text.extend([
+                    navigate,
+                    keys.hint(&[Action::Confirm], "Apply"),
+                    keys.hint(&[Action::Cancel, Action::Quit], "Cancel"),
+                ]);
+            }
+            // The command line takes the place of the footer
+            AppFocus::Command | AppFocus::SaveSearch | AppFocus::QuickAdd => {}
+            AppFocus::Calendar => {
+                footer_text.extend([
+                    keys.hint(&[Action::Left, Action::Right], "Day"),
+                    keys.hint(&[Action::Up, Action::Down], "Week"),
+                    keys.hint(&[Action::PreviousPage, Action::NextPage], "Month"),
+                    keys.hint(&[Action::Confirm], "Show Day"),
+                    keys.hint(&[Action::NewTask], "New Task"),
+                    keys.hint(&[Action::Cancel, Action::Quit], "Close"),
+                ]);
             }
         }
         footer_text.join(" | ")
     }
 }
+
+impl fmt::Display for Bucket {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Bucket::Overdue => "Overdue",
+            Bucket::Earlier => "Earlier",
+            Bucket::Today => "Today",
+            Bucket::Tomorrow => "Tomorrow",
+            Bucket::ThisWeek => "This Week",
+            Bucket::Later => "Later",
+            Bucket::Someday => "Someday",
+        })
+    }
+}
+
+impl SortMode {
+    fn next(self) -> Self {
+        match self {
+            SortMode::DueAscending => SortMode::Buckets,
+            SortMode::Buckets => SortMode::DueDescending,
+            SortMode::DueDescending => SortMode::Status,
+            SortMode::Status => SortMode::Title,
+            SortMode::Title => SortMode::Created,
+            SortMode::Created => SortMode::Updated,
+            SortMode::Updated => SortMode::Completed,
+            SortMode::Completed => SortMode::None,
+            SortMode::None => SortMode::DueAscending,
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "due" | "time" => Some(SortMode::DueAscending),
+            "buckets" => Some(SortMode::Buckets),
+            "due-desc" => Some(SortMode::DueDescending),
+            "status" => Some(SortMode::Status),
+            "title" => Some(SortMode::Title),
+            "created" => Some(SortMode::Created),
+            "updated" => Some(SortMode::Updated),
+            "completed" => Some(SortMode::Completed),
+            "none" => Some(SortMode::None),
+            _ => None,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            SortMode::DueAscending => "By Due Date",
+            SortMode::Buckets => "Today, Upcoming and Later",
+            SortMode::DueDescending => "By Due Date, Latest First",
+            SortMode::Status => "By Status",
+            SortMode::Title => "By Title",
+            SortMode::Created => "Recently Created",
+            SortMode::Updated => "Recently Updated",
+            SortMode::Completed => "Recently Completed",
+            SortMode::None => "Ungrouped",
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use ratatui::{
+    prelude::*,
+    widgets::{Clear, List, ListItem, ListState},
+};
+
+use crate::tasks::{Task, TaskStatus};
+
+use super::task_style;
+
+/// Tasks in one column per status.
+pub struct Board {
+    columns: Vec<(TaskStatus, Vec<Task>)>,
+    selected: Option<u128>,
+}
+
+impl Widget for &mut Board {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let count = self.columns.len() as u32;
+        let areas =
+            Layout::horizontal(vec![Constraint::Ratio(1, count); self.columns.len()]).split(area);
+        let position = self.position();
+        let theme = crate::theme::current();
+
+        for (i, ((status, tasks), area)) in self.columns.iter().zip(areas.iter()).enumerate() {
+            let border_style = if position.is_some_and(|(column, _)| column == i) {
+                theme.primary
+            } else {
+                theme.secondary
+            };
+            let title = format!(" {} ({}) ", status.as_str(), tasks.len());
+            let block = crate::helpers::rounded_block(title.into(), border_style.into());
+
+            let items: Vec<ListItem> = tasks
+                .iter()
+                .map(|task| ListItem::new(task.title.as_str()).style(task_style(task, &theme)))
+                .collect();
+
+            let mut state = ListState::default()
+                .with_selected(position.and_then(|(column, row)| (column == i).then_some(row)));
+            let list = List::new(items)
+                .block(block)
+                .highlight_style(Style::new().fg(theme.selection).reversed());
+            StatefulWidget::render(list, *area, buf, &mut state);
+        }
+    }
+}
+
+impl Board {
+    pub fn new() -> Self {
+        Self {
+            columns: Vec::new(),
+            selected: None,
+        }
+    }
+
+    /// Sorts the tasks into their columns by due date and sets the one to highlight.
+    pub fn set_tasks(&mut self, tasks: &[Task], selected: Option<u128>) {
+        let mut statuses: Vec<TaskStatus> = crate::tasks::statuses()
+            .iter()
+            .map(|status| TaskStatus::from(status.name.clone()))
+            .collect();
+        // Statuses that were removed from the config still get a column
+        for task in tasks {
+            if !statuses.contains(&task.status) {
+                statuses.push(task.status.clone());
+            }
+        }
+
+        self.columns = statuses
+            .into_iter()
+            .map(|status| {
+                let mut column: Vec<Task> = tasks
+                    .iter()
+                    .filter(|task| task.status == status)
+                    .cloned()
+                    .collect();
+                column.sort_by_key(|task| task.due_key());
+                (status, column)
+            })
+            .collect();
+        self.selected = selected;
+    }
+
+    // Column and row of the selected task
+    fn position(&self) -> Option<(usize, usize)> {
+        self.columns
+            .iter()
+            .enumerate()
+            .find_map(|(column, (_, tasks))| {
+                let row = tasks
+                    .iter()
+                    .position(|task| Some(task.id) == self.selected)?;
+                Some((column, row))
+            })
+    }
+
+    /// Returns the task next to the selected one or the first task if none is selected.
+    pub fn step(&self, columns: isize, rows: isize) -> Option<u128> {
+        let Some((column, row)) = self.position() else {
+            return self.first_task(0, 1);
+        };
+        if columns != 0 {
+            // Skip over empty columns, stay put at the edges
+            let column = column.checked_add_signed(columns)?;
+            let (_, tasks) = self.columns.get(column)?;
+            return match tasks.get(row).or(tasks.last()) {
+                Some(task) => Some(task.id),
+                None => self.first_task(column, columns).or(self.selected),
+            };
+        }
+        let tasks = &self.columns[column].1;
+        let row = (row as isize + rows).rem_euclid(tasks.len() as isize) as usize;
+        Some(tasks[row].id)
+    }
+
+    // First task of the first non empty column starting at `column` in the direction
+    fn first_task(&self, column: usize, direction: isize) -> Option<u128> {
+        let mut column = Some(column);
+        while let Some(index) = column {
+            let (_, tasks) = self.columns.get(index)?;
+            if let Some(task) = tasks.first() {
+                return Some(task.id);
+            }
+            column = index.checked_add_signed(direction);
+        }
+        None
+    }
+
+    /// Status of the column next to the one of the selected task.
+    pub fn next_status(&self, direction: isize) -> Option<TaskStatus> {
+        let (column, _) = self.position()?;
+        let column = column.checked_add_signed(direction)?;
+        self.columns.get(column).map(|(status, _)| status.clone())
+    }
+}
@@ -0,0 +1,184 @@
+use chrono::{Datelike, Days, Months, NaiveDate};
+use ratatui::{
+    crossterm::event::KeyEvent,
+    prelude::*,
+    widgets::{Cell, Clear, Row, Table},
+};
+use std::collections::HashMap;
+
+use crate::{
+    keybindings::{Action, Keybindings},
+    tasks::Task,
+    theme::Theme,
+};
+
+/// Month view of the tasks, also the date picker of the task editor.
+#[derive(Clone)]
+pub struct Calendar {
+    selected: NaiveDate,
+    days: HashMap<NaiveDate, Day>,
+}
+
+#[derive(Clone, Default)]
+struct Day {
+    tasks: usize,
+    overdue: bool,
+}
+
+pub enum CalendarAction {
+    None,
+    Close,
+    /// Show only the tasks of the date
+    Filter(NaiveDate),
+    NewTask(NaiveDate),
+}
+
+impl Widget for &mut Calendar {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let title = format!(" {} ", self.selected.format("%B %Y"));
+        let theme = crate::theme::current();
+        let block =
+            crate::helpers::rounded_block(Line::from(title).centered(), theme.primary.into());
+
+        let header = Row::new(["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"])
+            .style(Style::new().fg(theme.header).bold())
+            .bottom_margin(1);
+
+        let today = chrono::Local::now().date_naive();
+        let first = self.selected.with_day(1).unwrap();
+        // Start the grid on the monday before the first day of the month
+        let start = first - Days::new(first.weekday().num_days_from_monday() as u64);
+
+        // Space the weeks out when the area is tall enough for it
+        let margin = u16::from(area.height >= 18);
+        let rows = (0..6).map(|week| {
+            let cells = (0..7).map(|weekday| {
+                let date = start + Days::new(week * 7 + weekday);
+                self.render_day(date, today, &theme)
+            });
+            Row::new(cells).bottom_margin(margin)
+        });
+
+        let table = Table::new(rows, [Constraint::Ratio(1, 7); 7])
+            .header(header)
+            .block(block);
+        Widget::render(table, area, buf);
+    }
+}
+
+impl Calendar {
+    pub fn new() -> Self {
+        Self {
+            selected: chrono::Local::now().date_naive(),
+            days: HashMap::new(),
+        }
+    }
+
+    pub fn select(&mut self, date: NaiveDate) {
+        self.selected = date;
+    }
+
+    pub fn selected(&self) -> NaiveDate {
+        self.selected
+    }
+
+    pub fn set_tasks(&mut self, tasks: &[Task]) {
+        self.days.clear();
+        for task in tasks {
+            let Some(date) = task.day() else {
+                continue;
+            };
+            let day = self.days.entry(date).or_default();
+            day.tasks += 1;
+            day.overdue |= task.is_overdue();
+        }
+    }
+
+    fn render_day(&self, date: NaiveDate, today: NaiveDate, theme: &Theme) -> Cell<'_> {
+        let day = self.days.get(&date);
+        let text = match day {
+            Some(day) if day.overdue => format!("{:>2} ({})!", date.day(), day.tasks),
+            Some(day) => format!("{:>2} ({})", date.day(), day.tasks),
+            None => format!("{:>2}", date.day()),
+        };
+
+        let weekend = date.weekday().num_days_from_monday() >= 5;
+        let mut style = if date.month() != self.selected.month() {
+            Style::new().fg(theme.completed)
+        } else if day.is_some_and(|day| day.overdue) {
+            Style::new().fg(theme.overdue)
+        } else if weekend {
+            Style::new().fg(theme.secondary)
+        } else {
+            Style::default()
+        };
+        if day.is_some() {
+            style = style.bold();
+        }
+        if date == today {
+            style = style.bold().underlined();
+        }
+        if date == self.selected {
+            style = style.fg(theme.selection).reversed();
+        }
+        Cell::from(text).style(style)
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent, keys: &Keybindings) -> CalendarAction {
+        let actions = [
+            Action::Left,
+            Action::Right,
+            Action::Up,
+            Action::Down,
+            Action::PreviousPage,
+            Action::NextPage,
+            Action::Confirm,
+            Action::NewTask,
+            Action::Cancel,
+            Action::Quit,
+        ];
+        match keys.action(&key, &actions) {
+            Some(Action::Confirm) => return CalendarAction::Filter(self.selected),
+            Some(Action::NewTask) => return CalendarAction::NewTask(self.selected),
+            Some(Action::Cancel | Action::Quit) => return CalendarAction::Close,
+            Some(action) => self.step(action),
+            None => {}
+        }
+        CalendarAction::None
+    }
+
+    /// Moves the selection for the arrow and page keys, `false` for any other key.
+    pub fn move_selection(&mut self, key: KeyEvent, keys: &Keybindings) -> bool {
+        let actions = [
+            Action::Left,
+            Action::Right,
+            Action::Up,
+            Action::Down,
+            Action::PreviousPage,
+            Action::NextPage,
+        ];
+        let Some(action) = keys.action(&key, &actions) else {
+            return false;
+        };
+        self.step(action);
+        true
+    }
+
+    // Days for left and right, weeks for up and down and months for the page keys
+    fn step(&mut self, action: Action) {
+        let selected = match action {
+            Action::Left => self.selected.checked_sub_days(Days::new(1)),
+            Action::Right => self.selected.checked_add_days(Days::new(1)),
+            Action::Up => self.selected.checked_sub_days(Days::new(7)),
+            Action::Down => self.selected.checked_add_days(Days::new(7)),
+            Action::PreviousPage => self.selected.checked_sub_months(Months::new(1)),
+            Action::NextPage => self.selected.checked_add_months(Months::new(1)),
+            _ => None,
+        };
+        if let Some(selected) = selected {
+            self.selected = selected;
+        }
+    }
+}
@@ -3,10 +3,10 @@ use ratatui::{
     widgets::{Clear, Paragraph, Widget, Wrap},
 };
 
-use crate::{
-    helpers::{PopupSize, create_popup_area},
-    ui::{GREEN_STYLE, PRIMARY_STYLE, RED_STYLE},
-};
+use crate::helpers::{PopupSize, create_popup_area};
+
+// Width of ` [ Y ] `
+const BUTTON_WIDTH: u16 = 7;
 
 pub struct Confirm {
     title: String,
@@ -31,17 +31,34 @@ impl Confirm {
         }
     }
 
+    /// Whether a click at `position` hit the Y ( `true` ) or the N of the prompt in `area`.
+    pub fn answer_at(&self, area: Rect, position: Position) -> Option<bool> {
+        let popup_area = create_popup_area(area, &self.popup_size);
+        if position.y != popup_area.bottom().saturating_sub(1) {
+            return None;
+        }
+        // The buttons are centered between the corners with a space in between
+        let width = BUTTON_WIDTH * 2 + 1;
+        let start = popup_area.x + 1 + popup_area.width.saturating_sub(2 + width) / 2;
+        match position.x.checked_sub(start)? {
+            x if x < BUTTON_WIDTH => Some(true),
+            x if x > BUTTON_WIDTH && x < width => Some(false),
+            _ => None,
+        }
+    }
+
     fn render_prompt(&self, area: Rect, buf: &mut Buffer) {
-        let block = crate::helpers::rounded_block(self.title.as_str().into(), PRIMARY_STYLE)
+        let theme = crate::theme::current();
+        let block = crate::helpers::rounded_block(self.title.as_str().into(), theme.primary.into())
             // Should separate into two title_bottom for a separation line in between text
             .title_bottom(vec![
                 Span::styled(" [ ", Style::reset()),
-                Span::styled("Y", GREEN_STYLE.bold()),
+                Span::styled("Y", Style::new().fg(theme.success).bold()),
                 Span::styled(" ] ", Style::reset()),
             ])
             .title_bottom(vec![
                 Span::styled(" [ ", Style::reset()),
-                Span::styled("N", RED_STYLE.bold()),
+                Span::styled("N", Style::new().fg(theme.error).bold()),
                 Span::styled(" ] ", Style::reset()),
             ])
             .title_alignment(Alignment::Center);
@@ -1,10 +1,20 @@
+mod agenda;
 mod app;
+mod board;
+mod calendar;
 mod confirm;
 mod new_task;
 mod overdue;
+mod time_picker;
+mod vim;
 
+pub(super) use agenda::Agenda;
 pub use app::App;
-pub(super) use app::{GREEN_STYLE, PRIMARY_STYLE, RED_STYLE, SELECTION_STYLE};
+pub(super) use app::task_style;
+pub(super) use board::Board;
+pub(super) use calendar::{Calendar, CalendarAction};
 pub(super) use confirm::Confirm;
 pub(super) use new_task::NewTask;
 pub(super) use overdue::OverDue;
+pub(super) use time_picker::TimePicker;
+pub(super) use vim::{Vim, VimAction};
@@ -1,4 +1,4 @@
-use chrono::{NaiveDate, NaiveTime};
+use chrono::{NaiveDate, NaiveTime, Timelike};
 use ratatui::{
     crossterm::event::{KeyCode, KeyEvent},
     prelude::*,
@@ -6,15 +6,29 @@ use ratatui::{
 };
 use tui_textarea::{CursorMove, TextArea};
 
-use crate::tasks::{Task, TaskStatus};
+use super::{Calendar, TimePicker};
+use crate::{
+    dates::Due,
+    keybindings::{Action, Keybindings},
+    tasks::Task,
+};
+
+const DATE_TITLE: &str = " Date ";
+const TIME_TITLE: &str = " Time ";
 
-use super::RED_STYLE;
+// Borders, the weekday header with its margin and six weeks
+const DATE_PICKER_HEIGHT: u16 = 10;
+// Borders and ` 09 : 30 `
+const TIME_PICKER_SIZE: (u16, u16) = (15, 3);
 
 #[derive(Clone)]
 pub struct NewTask<'a> {
     focus: Focus,
     mode: Mode,
     widgets: Widgets<'a>,
+    /// Opened under the date field while inserting, with the days that have tasks
+    calendar: Calendar,
+    time_picker: TimePicker,
     task: Task,
     pub quit: bool,
     pub completed: bool,
@@ -59,6 +73,28 @@ impl Widget for &mut NewTask<'_> {
         self.render_date(date_area, buf);
         self.render_time(time_area, buf);
         self.widgets.description.render(description_area, buf);
+
+        // The pickers open under the focused field while inserting
+        if self.mode == Mode::Insert {
+            match self.focus {
+                Focus::Date => {
+                    self.calendar.select(self.picker_date());
+                    let picker = Rect {
+                        y: date_area.bottom(),
+                        height: DATE_PICKER_HEIGHT,
+                        ..date_area
+                    };
+                    self.calendar.render(picker.intersection(area), buf);
+                }
+                Focus::Time => {
+                    self.time_picker.select(self.picker_time());
+                    let (width, height) = TIME_PICKER_SIZE;
+                    let picker = Rect::new(time_area.x, time_area.bottom(), width, height);
+                    self.time_picker.render(picker.intersection(area), buf);
+                }
+                _ => {}
+            }
+        }
     }
 }
 
@@ -99,22 +135,18 @@ impl Widgets<'_> {
     }
 
     fn setup_widgets(widgets: &mut [&mut TextArea]) {
-        // Helper function to create a bordered block
-        fn get_block(title: &str) -> Block {
-            Block::bordered()
-                .title(title)
-                .border_type(BorderType::Rounded)
-        }
-
         let titles = [
             (" Title ", "Enter your task title"),
-            (" Date - (DD MM YYYY) ", "Enter your task date"),
-            (" Time - (HH MM) ", "Enter your estimated completion time"),
+            (
+                DATE_TITLE,
+                "tomorrow, next fri 5pm, in 3 days, DD MM YYYY or empty",
+            ),
+            (TIME_TITLE, "5pm, 14:30 or empty for any time of the day"),
             (" Description ", "Enter your task description"),
         ];
 
         for (widget, (title, placeholder)) in widgets.iter_mut().zip(titles.iter()) {
-            widget.set_block(get_block(title));
+            widget.set_block(field_block(title));
             widget.set_placeholder_text(*placeholder);
             widget.set_cursor_line_style(Style::default()); // Remove the underline when typing
             widget.move_cursor(CursorMove::End);
@@ -151,43 +183,95 @@ impl NewTask<'_> {
             completed: false,
             task: Task::new(),
             widgets: Widgets::new(),
+            calendar: Calendar::new(),
+            time_picker: TimePicker::new(),
         }
     }
 
+    /// The tasks highlighted in the date picker.
+    pub fn set_tasks(&mut self, tasks: &[Task]) {
+        self.calendar.set_tasks(tasks);
+    }
+
+    // The parsed time is shown below the field as it is typed
     fn render_time(&mut self, area: Rect, buf: &mut Buffer) {
-        let time_val = self.widgets.time.lines()[0].to_string();
-        let time = NaiveTime::parse_from_str(&time_val, "%H %M");
-        let style = match time {
-            Ok(_) => Style::default(),
-            Err(_) => RED_STYLE,
+        let preview = match self.parsed_time() {
+            Ok(Some(time)) => Ok(time.format("%H:%M").to_string()),
+            Ok(None) => Ok("Any time".to_string()),
+            Err(error) => Err(error),
         };
-        self.widgets.time.set_cursor_line_style(style);
+        let block = field_block(TIME_TITLE).title_bottom(preview_line(preview));
+        self.widgets.time.set_block(block);
         self.widgets.time.render(area, buf);
     }
 
+    // The date field shows the whole due date, including a time typed into either field
     fn render_date(&mut self, area: Rect, buf: &mut Buffer) {
-        let date_val = self.widgets.date.lines()[0].to_string();
-        let date = NaiveDate::parse_from_str(&date_val, "%d %m %Y");
-        let style = match date {
-            Ok(_) => Style::default(),
-            Err(_) => RED_STYLE,
+        let preview = match self.parsed_time() {
+            // Mistakes in the time are shown on the time field only
+            Err(_) => self.parsed_date().map(|due| due.describe()),
+            Ok(_) => self.due().map(|due| due.describe()),
         };
-        self.widgets.date.set_cursor_line_style(style);
+        let block = field_block(DATE_TITLE).title_bottom(preview_line(preview));
+        self.widgets.date.set_block(block);
         self.widgets.date.render(area, buf);
     }
 
+    // Empty for tasks without a date, the date field may hold a time too
+    fn parsed_date(&self) -> Result<Due, &'static str> {
+        let text = self.widgets.date.lines()[0].trim();
+        match Due::parse(text, chrono::Local::now().date_naive()) {
+            Some(due) if due.date.is_some() || text.is_empty() => Ok(due),
+            _ => Err("Not a date"),
+        }
+    }
+
+    fn parsed_time(&self) -> Result<Option<NaiveTime>, &'static str> {
+        let text = self.widgets.time.lines()[0].trim();
+        match Due::parse(text, chrono::Local::now().date_naive()) {
+            Some(due) if due.date.is_none() => Ok(due.time),
+            _ => Err("Not a time"),
+        }
+    }
+
+    /// Both fields read together, both can be left empty but a time needs a date.
+    fn due(&self) -> Result<Due, &'static str> {
+        let date = self.parsed_date()?;
+        let time = self.parsed_time()?;
+        if date.time.is_some() && time.is_some() {
+            return Err("Two times given");
+        }
+        let due = Due {
+            date: date.date,
+            time: date.time.or(time),
+        };
+        if due.date.is_none() && due.time.is_some() {
+            return Err("A time needs a date");
+        }
+        Ok(due)
+    }
+
     pub fn from(task: Task) -> Self {
         let description = task.description.lines().map(|s| s.to_string()).collect();
         let date = vec![task.date];
         let time = vec![task.time];
         let title = vec![task.title];
         Self {
-            focus: Focus::Title,
-            mode: Mode::Normal,
-            quit: false,
-            completed: false,
-            task: Task::from(task.id),
+            task: Task {
+                status: task.status,
+                ..Task::from(task.id)
+            },
             widgets: Widgets::from(title, date, time, description),
+            ..Self::new()
+        }
+    }
+
+    pub fn with_date(date: NaiveDate) -> Self {
+        let empty = || vec![String::new()];
+        let date = vec![date.format("%d %m %Y").to_string()];
+        Self {
+            widgets: Widgets::from(empty(), date, empty(), empty()),
+            ..Self::new()
         }
     }
 
@@ -208,38 +292,46 @@ impl NewTask<'_> {
         self.widgets.description.set_cursor_style(cursor_styles[3]);
     }
 
-    pub fn handle_key(&mut self, key: KeyEvent) {
+    pub fn handle_key(&mut self, key: KeyEvent, keys: &Keybindings) {
         match self.mode {
-            Mode::Normal => match key.code {
-                KeyCode::Tab => self.quit = true,
-                KeyCode::Char('i') => self.mode = Mode::Insert,
-                KeyCode::Enter => {
-                    if self.widgets.date.cursor_line_style() == RED_STYLE
-                        || self.widgets.time.cursor_line_style() == RED_STYLE
-                    {
-                        return;
-                    }
-                    self.mode = Mode::Normal;
-                    self.task = Task {
-                        id: self.task.id,
-                        title: self.widgets.title.lines()[0].to_string(),
-                        date: self.widgets.date.lines()[0].to_string(),
-                        time: self.widgets.time.lines()[0].to_string(),
-                        description: self.widgets.description.lines().join("\n"),
-                        status: TaskStatus::Pending,
-                    };
-                    if self.task.is_overdue() {
-                        self.task.status = TaskStatus::OverDue;
+            Mode::Normal => {
+                match keys.action(&key, &[Action::SwitchFocus, Action::Insert, Action::Save]) {
+                    Some(Action::SwitchFocus) => self.quit = true,
+                    Some(Action::Insert) => self.mode = Mode::Insert,
+                    Some(Action::Save) => {
+                        let Ok(due) = self.due() else {
+                            return;
+                        };
+                        self.mode = Mode::Normal;
+                        self.task = Task {
+                            id: self.task.id,
+                            title: self.widgets.title.lines()[0].to_string(),
+                            // Stored in the fixed formats whatever was typed
+                            date: due
+                                .date
+                                .map(|date| date.format("%d %m %Y").to_string())
+                                .unwrap_or_default(),
+                            time: due
+                                .time
+                                .map(|time| time.format("%H %M").to_string())
+                                .unwrap_or_default(),
+                            description: self.widgets.description.lines().join("\n"),
+                            status: self.task.status.clone(),
+                            ..Task::from(self.task.id)
+                        };
+                        self.quit = true;
+                        self.completed = true;
                     }
-                    self.quit = true;
-                    self.completed = true;
+                    _ => {}
                 }
-                _ => {}
-            },
-            Mode::Insert => match key.code {
-                KeyCode::Esc => self.mode = Mode::Normal,
-                KeyCode::Tab => self.focus = self.focus.next(),
-                KeyCode::BackTab => self.focus = self.focus.previous(),
+            }
+            Mode::Insert => match keys.action(
+                &key,
+                &[Action::NormalMode, Action::NextField, Action::PreviousField],
+            ) {
+                Some(Action::NormalMode) => self.mode = Mode::Normal,
+                Some(Action::NextField) => self.focus = self.focus.next(),
+                Some(Action::PreviousField) => self.focus = self.focus.previous(),
                 _ => match self.focus {
                     Focus::Title => {
                         if key.code != KeyCode::Enter {
@@ -247,12 +339,12 @@ impl NewTask<'_> {
                         }
                     }
                     Focus::Date => {
-                        if key.code != KeyCode::Enter {
+                        if key.code != KeyCode::Enter && !self.pick_date(key, keys) {
                             self.widgets.date.input(key);
                         }
                     }
                     Focus::Time => {
-                        if key.code != KeyCode::Enter {
+                        if key.code != KeyCode::Enter && !self.pick_time(key, keys) {
                             self.widgets.time.input(key);
                         }
                     }
@@ -264,15 +356,110 @@ impl NewTask<'_> {
         }
     }
 
+    // Arrow keys go to the picker and write its date into the field, characters are typed
+    fn pick_date(&mut self, key: KeyEvent, keys: &Keybindings) -> bool {
+        if matches!(key.code, KeyCode::Char(_)) {
+            return false;
+        }
+        self.calendar.select(self.picker_date());
+        if !self.calendar.move_selection(key, keys) {
+            return false;
+        }
+        let mut text = self.calendar.selected().format("%d %m %Y").to_string();
+        // A time typed into the date field is kept
+        if let Some(time) = self.parsed_date().ok().and_then(|due| due.time) {
+            text = format!("{} {}", text, time.format("%H %M"));
+        }
+        set_text(&mut self.widgets.date, text);
+        true
+    }
+
+    fn pick_time(&mut self, key: KeyEvent, keys: &Keybindings) -> bool {
+        if matches!(key.code, KeyCode::Char(_)) {
+            return false;
+        }
+        let time = self.picker_time();
+        self.time_picker.select(time);
+        if !self.time_picker.handle_key(key, keys) {
+            return false;
+        }
+        if self.time_picker.selected() != time {
+            let text = self.time_picker.selected().format("%H %M").to_string();
+            set_text(&mut self.widgets.time, text);
+        }
+        true
+    }
+
+    // The date in the field, today when there is none yet
+    fn picker_date(&self) -> NaiveDate {
+        let today = chrono::Local::now().date_naive();
+        let date = self.parsed_date().ok().and_then(|due| due.date);
+        date.unwrap_or(today)
+    }
+
+    // The time in either field, the next full hour when there is none yet
+    fn picker_time(&self) -> NaiveTime {
+        let date = self.parsed_date().ok().and_then(|due| due.time);
+        let time = self.parsed_time().ok().flatten().or(date);
+        let hour = (chrono::Local::now().hour() + 1) % 24;
+        time.unwrap_or_else(|| NaiveTime::from_hms_opt(hour, 0, 0).unwrap_or(NaiveTime::MIN))
+    }
+
     pub fn get_task(&self) -> &Task {
         &self.task
     }
 
-    pub fn footer_text(&self) -> Box<[&str]> {
-        let footer = match self.mode {
-            Mode::Normal => vec!["[Tab] Switch Fields", "[i] Insert Mode", "[Enter] Save"],
-            Mode::Insert => vec!["[Esc] Normal Mode", "[Tab] Switch Fields"],
-        };
-        footer.into_boxed_slice()
+    pub fn footer_text(&self, keys: &Keybindings) -> Vec<String> {
+        match self.mode {
+            Mode::Normal => vec![
+                keys.hint(&[Action::SwitchFocus], "Focus Tasks"),
+                keys.hint(&[Action::Insert], "Insert Mode"),
+                keys.hint(&[Action::Save], "Save"),
+            ],
+            Mode::Insert => {
+                let mut footer = vec![
+                    keys.hint(&[Action::NormalMode], "Normal Mode"),
+                    keys.hint(&[Action::NextField, Action::PreviousField], "Switch Fields"),
+                ];
+                match self.focus {
+                    Focus::Date => footer.extend([
+                        keys.hint(
+                            &[Action::Left, Action::Right, Action::Up, Action::Down],
+                            "Pick Date",
+                        ),
+                        keys.hint(&[Action::PreviousPage, Action::NextPage], "Month"),
+                    ]),
+                    Focus::Time => footer.extend([
+                        keys.hint(&[Action::Left, Action::Right], "Hour/Minute"),
+                        keys.hint(&[Action::Up, Action::Down], "Change"),
+                    ]),
+                    _ => {}
+                }
+                footer
+            }
+        }
+    }
+}
+
+// Replaces the whole text of a single line field
+fn set_text(widget: &mut TextArea, text: String) {
+    widget.select_all();
+    widget.delete_newline();
+    widget.insert_str(text);
+}
+
+fn field_block(title: &str) -> Block<'_> {
+    Block::bordered()
+        .title(title)
+        .border_type(BorderType::Rounded)
+}
+
+// What a field was read as, or why it couldn't be
+fn preview_line(preview: Result<String, &str>) -> Line<'static> {
+    let theme = crate::theme::current();
+    match preview {
+        Ok(text) => Line::styled(format!(" {} ", text), theme.success),
+        Err(error) => Line::styled(format!(" {} ", error), theme.error),
     }
+    .right_aligned()
 }
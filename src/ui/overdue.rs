@@ -1,13 +1,15 @@
-use chrono::NaiveDateTime;
 use ratatui::{
-    crossterm::event::{KeyCode, KeyEvent},
+    crossterm::event::KeyEvent,
     prelude::*,
     widgets::{Cell, Clear, Row, Table, TableState},
 };
 
-use crate::{helpers::PopupSize, tasks::Task};
-
-use super::{PRIMARY_STYLE, SELECTION_STYLE};
+use crate::{
+    helpers::PopupSize,
+    keybindings::{Action, Keybindings},
+    storage::{Query, Storage},
+    tasks::{Task, TaskStatus},
+};
 
 pub struct OverDue {
     state: TableState,
@@ -19,16 +21,18 @@ impl Widget for &mut OverDue {
         let area = crate::helpers::create_popup_area(area, &PopupSize::Percentage { x: 50, y: 50 });
         Clear.render(area, buf);
 
-        let block = crate::helpers::rounded_block(" Overdues ".into(), PRIMARY_STYLE);
+        let theme = crate::theme::current();
+        let block = crate::helpers::rounded_block(" Overdues ".into(), theme.primary.into());
         let mut rows: Vec<Row> = Vec::new();
 
         for task in &self.tasks {
             let title = task.title.as_str();
-            let date_time_str = format!("{} {}", task.time, task.date);
-            let date_time =
-                NaiveDateTime::parse_from_str(&date_time_str, "%H %M %d %m %Y").unwrap();
-            let date_time = date_time.format("%d-%m-%Y %H:%M");
-            let row = Row::new(vec![Cell::from(title), Cell::from(date_time.to_string())]);
+            let date_time = match (task.day(), task.time_of_day()) {
+                (Some(day), Some(time)) => day.and_time(time).format("%d-%m-%Y %H:%M").to_string(),
+                (Some(day), None) => day.format("%d-%m-%Y").to_string(),
+                (None, _) => String::new(),
+            };
+            let row = Row::new(vec![Cell::from(title), Cell::from(date_time)]);
             rows.push(row);
         }
 
@@ -41,7 +45,7 @@ impl Widget for &mut OverDue {
             &[Constraint::Percentage(75), Constraint::Percentage(25)],
         )
         .header(headers)
-        .row_highlight_style(SELECTION_STYLE)
+        .row_highlight_style(theme.selection)
         .block(block);
 
         StatefulWidget::render(table, area, buf, &mut self.state);
@@ -56,27 +60,28 @@ impl OverDue {
         }
     }
 
-    pub fn get_tasks(tasks: &[Task]) -> Vec<Task> {
-        let mut tasks: Vec<Task> = tasks
+    pub fn get_tasks(storage: &dyn Storage) -> Vec<Task> {
+        let open = crate::tasks::statuses()
             .iter()
-            .filter(|task| task.is_overdue())
-            .cloned()
+            .filter(|status| !status.done)
+            .map(|status| TaskStatus::from(status.name.clone()))
             .collect();
-        tasks.sort_by_key(|task| {
-            NaiveDateTime::parse_from_str(
-                format!("{} {}", task.time, task.date).as_str(),
-                "%H %M %d %m %Y",
-            )
-            .unwrap()
-        });
+        let query = Query {
+            statuses: open,
+            due_to: Some(chrono::Local::now().naive_local()),
+            ..Default::default()
+        };
+        let mut tasks = storage.query(&query).unwrap_or_default();
+        tasks.sort_by_key(|task| task.due_key());
         tasks
     }
 
-    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
-        match key.code {
-            KeyCode::Char('q') => return true,
-            KeyCode::Down => self.state.select_next(),
-            KeyCode::Up => self.state.select_previous(),
+    pub fn handle_key(&mut self, key: KeyEvent, keys: &Keybindings) -> bool {
+        let actions = [Action::Cancel, Action::Quit, Action::Down, Action::Up];
+        match keys.action(&key, &actions) {
+            Some(Action::Cancel | Action::Quit) => return true,
+            Some(Action::Down) => self.state.select_next(),
+            Some(Action::Up) => self.state.select_previous(),
             _ => {}
         }
         false
@@ -0,0 +1,86 @@
+use chrono::{NaiveTime, Timelike};
+use ratatui::{
+    crossterm::event::KeyEvent,
+    prelude::*,
+    widgets::{Clear, Paragraph},
+};
+
+use crate::keybindings::{Action, Keybindings};
+
+// Steps of the minute spinner
+const MINUTE_STEP: u32 = 5;
+
+/// Hour and minute spinner shown under the time field of the task editor.
+#[derive(Clone)]
+pub struct TimePicker {
+    time: NaiveTime,
+    /// Whether the arrows change the minutes instead of the hour
+    minutes: bool,
+}
+
+impl Widget for &TimePicker {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+        let theme = crate::theme::current();
+        let block = crate::helpers::rounded_block(" Time ".into(), theme.primary.into());
+
+        let style = |selected: bool| match selected {
+            true => Style::new().fg(theme.selection).reversed(),
+            false => Style::default(),
+        };
+        let line = Line::from(vec![
+            Span::styled(format!(" {:02} ", self.time.hour()), style(!self.minutes)),
+            Span::raw(":"),
+            Span::styled(format!(" {:02} ", self.time.minute()), style(self.minutes)),
+        ]);
+        Paragraph::new(line)
+            .centered()
+            .block(block)
+            .render(area, buf);
+    }
+}
+
+impl TimePicker {
+    pub fn new() -> Self {
+        Self {
+            time: NaiveTime::MIN,
+            minutes: false,
+        }
+    }
+
+    pub fn select(&mut self, time: NaiveTime) {
+        self.time = time;
+    }
+
+    pub fn selected(&self) -> NaiveTime {
+        self.time
+    }
+
+    /// Left and right pick the hour or the minutes, up and down change them. `false` for any
+    /// other key.
+    pub fn handle_key(&mut self, key: KeyEvent, keys: &Keybindings) -> bool {
+        let actions = [Action::Left, Action::Right, Action::Up, Action::Down];
+        let (hour, minute) = (self.time.hour(), self.time.minute());
+        // Both wrap around, the hour stays when the minutes do
+        let (hour, minute) = match keys.action(&key, &actions) {
+            Some(Action::Left | Action::Right) => {
+                self.minutes = !self.minutes;
+                return true;
+            }
+            Some(Action::Up) if self.minutes => {
+                (hour, (minute / MINUTE_STEP + 1) * MINUTE_STEP % 60)
+            }
+            Some(Action::Down) if self.minutes => (
+                hour,
+                (minute.div_ceil(MINUTE_STEP) * MINUTE_STEP + 60 - MINUTE_STEP) % 60,
+            ),
+            Some(Action::Up) => ((hour + 1) % 24, minute),
+            Some(Action::Down) => ((hour + 23) % 24, minute),
+            _ => return false,
+        };
+        if let Some(time) = NaiveTime::from_hms_opt(hour, minute, 0) {
+            self.time = time;
+        }
+        true
+    }
+}
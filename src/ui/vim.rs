@@ -0,0 +1,95 @@
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use tui_textarea::TextArea;
+
+use crate::keybindings::Action;
+
+/// Counts, multi key commands and the `:` command line of the vim mode.
+pub struct Vim<'a> {
+    count: Option<usize>,
+    pending: Option<char>,
+    pub command_line: TextArea<'a>,
+    /// Result of the last command or key, shown in place of the footer
+    pub message: Option<String>,
+}
+
+pub enum VimAction {
+    /// The key started or extended a count or a command
+    Pending,
+    Move(isize),
+    First,
+    Last,
+    /// A count followed by `G`, starting at 1
+    Line(usize),
+    HalfPageDown,
+    HalfPageUp,
+    Delete,
+    CommandLine,
+}
+
+impl Vim<'_> {
+    pub fn new() -> Self {
+        let mut command_line = TextArea::default();
+        command_line.set_cursor_line_style(Default::default());
+        Self {
+            count: None,
+            pending: None,
+            command_line,
+            message: None,
+        }
+    }
+
+    /// Handles the vim keys of the task list, `action` is the navigation or `Command` the key is
+    /// bound to.
+    /// Returns `None` for keys that are left to the normal bindings.
+    pub fn handle_key(&mut self, key: KeyEvent, action: Option<Action>) -> Option<VimAction> {
+        self.message = None;
+        let pending = self.pending.take();
+        let count = self.count.take();
+
+        let vim_action = match (pending, key.code, key.modifiers) {
+            (Some('g'), KeyCode::Char('g'), _) => match count {
+                Some(line) => VimAction::Line(line),
+                None => VimAction::First,
+            },
+            (Some('d'), KeyCode::Char('d'), _) => VimAction::Delete,
+            // Anything else cancels the pending command
+            (Some(_), _, _) => return Some(VimAction::Pending),
+            (None, KeyCode::Char(c @ '0'..='9'), KeyModifiers::NONE)
+                if c != '0' || count.is_some() =>
+            {
+                let digit = c.to_digit(10).unwrap_or_default() as usize;
+                let count = count.unwrap_or_default().saturating_mul(10);
+                self.count = Some(count.saturating_add(digit));
+                VimAction::Pending
+            }
+            (None, KeyCode::Char(c @ ('g' | 'd')), KeyModifiers::NONE) => {
+                self.count = count;
+                self.pending = Some(c);
+                VimAction::Pending
+            }
+            (None, KeyCode::Char('G'), _) => match count {
+                Some(line) => VimAction::Line(line),
+                None => VimAction::Last,
+            },
+            (None, KeyCode::Char('d'), KeyModifiers::CONTROL) => VimAction::HalfPageDown,
+            (None, KeyCode::Char('u'), KeyModifiers::CONTROL) => VimAction::HalfPageUp,
+            (None, _, _) if action == Some(Action::Command) => {
+                self.command_line = Self::new().command_line;
+                VimAction::CommandLine
+            }
+            _ => {
+                let count = count.unwrap_or(1) as isize;
+                match action {
+                    Some(Action::Down) => VimAction::Move(count),
+                    Some(Action::Up) => VimAction::Move(-count),
+                    _ => return None,
+                }
+            }
+        };
+        Some(vim_action)
+    }
+
+    pub fn command(&self) -> String {
+        self.command_line.lines()[0].trim().to_string()
+    }
+}
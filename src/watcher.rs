@@ -0,0 +1,89 @@
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::{
+    ffi::OsString,
+    fs,
+    path::Path,
+    sync::mpsc::{self, Receiver},
+};
+
+/// Watches the task file for changes made by other programs.
+pub struct TaskWatcher {
+    // Dropping the watcher stops the notifications, so it has to live as long as the receiver
+    _watcher: RecommendedWatcher,
+    events: Receiver<notify::Result<Event>>,
+    file_name: OsString,
+}
+
+impl TaskWatcher {
+    pub fn new(path: &Path) -> notify::Result<Self> {
+        // Watch the directory instead of the file, sync tools replace files by renaming them
+        let (Some(dir), Some(file_name)) = (path.parent(), path.file_name()) else {
+            return Err(notify::Error::path_not_found());
+        };
+        fs::create_dir_all(dir)?;
+        let (tx, rx) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(tx)?;
+        watcher.watch(dir, RecursiveMode::NonRecursive)?;
+        Ok(Self {
+            _watcher: watcher,
+            events: rx,
+            file_name: file_name.to_os_string(),
+        })
+    }
+
+    /// Drains all pending events and returns whether any of them touched the task file.
+    pub fn changed(&self) -> bool {
+        self.events
+            .try_iter()
+            .flatten()
+            .filter(|event| self.is_task_file_event(event))
+            .count()
+            > 0
+    }
+
+    fn is_task_file_event(&self, event: &Event) -> bool {
+        let modifies = matches!(
+            event.kind,
+            EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+        );
+        modifies
+            && event.paths.iter().any(|path| {
+                path.file_name().is_some_and(|name| {
+                    let name = name.as_encoded_bytes();
+                    // SQLite writes through its journal files as well
+                    name.strip_prefix(self.file_name.as_encoded_bytes())
+                        .is_some_and(|suffix| matches!(suffix, b"" | b"-journal" | b"-wal"))
+                })
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{CreateKind, ModifyKind};
+
+    #[test]
+    fn only_counts_the_task_file_and_its_journals() {
+        let dir = std::env::temp_dir().join(format!("todo-tui-{}", uuid::Uuid::now_v7()));
+        let watcher = TaskWatcher::new(&dir.join("tasks.db")).unwrap();
+        let touches = |name: &str| {
+            let event = Event::new(EventKind::Modify(ModifyKind::Any)).add_path(dir.join(name));
+            watcher.is_task_file_event(&event)
+        };
+        assert!(touches("tasks.db"));
+        assert!(touches("tasks.db-journal"));
+        assert!(touches("tasks.db-wal"));
+        assert!(!touches("tasks.db.tmp"));
+        assert!(!touches("tasks.dbx"));
+        assert!(!touches("tasks.enc.db"));
+
+        let created =
+            Event::new(EventKind::Create(CreateKind::File)).add_path(dir.join("tasks.db"));
+        assert!(watcher.is_task_file_event(&created));
+        let accessed = Event::new(EventKind::Access(notify::event::AccessKind::Any))
+            .add_path(dir.join("tasks.db"));
+        assert!(!watcher.is_task_file_event(&accessed));
+        let _ = fs::remove_dir_all(dir);
+    }
+}